@@ -0,0 +1,76 @@
+mod route;
+
+use common::kafka::Kafka;
+use common::mqtt::{Message, MqttClient, ReconnectOptions};
+use common::utils::dotenv;
+use route::{KeyMode, Route};
+use std::{error::Error, time::Duration};
+
+const DEFAULT_CLIENT_ID: &str = "mqtt-kafka-bridge";
+const DEFAULT_ROUTES: &str = "sensors,devices/+/status";
+const SUBSCRIPTION_QOS: i32 = 1;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+	let kafka_topic = dotenv::get_var("KAFKA_TOPIC")?;
+	let routes =
+		route::parse_routes(&dotenv::parse_var::<String>("BRIDGE_ROUTES").unwrap_or(DEFAULT_ROUTES.to_owned()), &kafka_topic)?;
+	let key_mode: KeyMode = dotenv::parse_var("BRIDGE_KEY").unwrap_or_default();
+	let client_id = dotenv::parse_var::<String>("BRIDGE_CLIENT_ID").unwrap_or(DEFAULT_CLIENT_ID.to_owned());
+
+	let kafka = Kafka::producer()?;
+	let mut mqtt_client = MqttClient::new(&client_id)?.without_status_topic();
+	// Without a clean session the broker keeps the messages published while the bridge is offline
+	mqtt_client.connect(Duration::from_secs(60), false)?;
+	subscribe(&mqtt_client, &routes)?;
+
+	let reconnect = ReconnectOptions::from_env();
+	loop {
+		// The MQTT client blocks while waiting, so the runtime moves its other tasks off this thread
+		let Some(message) = tokio::task::block_in_place(|| mqtt_client.wait_for_message(POLL_INTERVAL)) else {
+			tokio::task::block_in_place(|| mqtt_client.ensure_connected(&reconnect));
+			continue;
+		};
+
+		forward(&kafka, &routes, key_mode, &reconnect, &message).await;
+	}
+}
+
+fn subscribe(mqtt_client: &MqttClient, routes: &[Route]) -> Result<(), common::mqtt::Error> {
+	for route in routes {
+		mqtt_client.subscribe(&route.filter, SUBSCRIPTION_QOS)?;
+		println!("Forwarding {} to Kafka", route.filter);
+	}
+
+	Ok(())
+}
+
+// A message is retried until Kafka acknowledges it, before the next one is read, so a Kafka outage skips none of them.
+// The MQTT client acknowledges messages to the broker on arrival and offers no manual acknowledgement, so the messages
+// received but not forwarded yet are lost if the bridge dies
+async fn forward(kafka: &Kafka, routes: &[Route], key_mode: KeyMode, retry: &ReconnectOptions, message: &Message) {
+	let Some(route) = routes.iter().find(|route| route.matches(message.topic())) else {
+		return;
+	};
+	let topic = route.kafka_topic(message.topic());
+	// Payloads are forwarded as is, only the device id of the key needs them to be UTF-8
+	let payload = message.payload();
+	let key = key_mode.key(message.topic(), std::str::from_utf8(payload).unwrap_or_default());
+
+	let mut attempts = 0;
+	while let Err(e) = kafka.produce_with_key(&topic, key.as_deref(), payload).await {
+		let delay = retry.delay(attempts, &mut rand::thread_rng());
+		attempts = attempts.saturating_add(1);
+		eprintln!(
+			"Failed to forward a message from {} to {}, retrying in {:.1}s: {}",
+			message.topic(),
+			topic,
+			delay.as_secs_f64(),
+			e
+		);
+		tokio::time::sleep(delay).await;
+	}
+
+	println!("Forwarded a message from {} to {}", message.topic(), topic);
+}
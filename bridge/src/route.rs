@@ -0,0 +1,117 @@
+use common::mqtt::{topic_matches, validate_filter};
+use std::str::FromStr;
+
+const TOPIC_PLACEHOLDER: &str = "{topic}";
+
+#[derive(Debug, PartialEq)]
+pub struct Route {
+	pub filter: String,
+	kafka_topic: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum KeyMode {
+	#[default]
+	DeviceId,
+	Topic,
+	None,
+}
+
+impl Route {
+	pub fn matches(&self, topic: &str) -> bool {
+		topic_matches(&self.filter, topic)
+	}
+
+	// Kafka topics can not contain slashes, so {topic} becomes the MQTT topic with its levels separated by dots
+	pub fn kafka_topic(&self, topic: &str) -> String {
+		self.kafka_topic.replace(TOPIC_PLACEHOLDER, &topic.replace('/', "."))
+	}
+}
+
+// Routes are separated by commas, each one a topic filter optionally followed by = and its Kafka topic
+pub fn parse_routes(routes: &str, default_topic: &str) -> Result<Vec<Route>, String> {
+	let routes = routes
+		.split(',')
+		.map(str::trim)
+		.filter(|route| !route.is_empty())
+		.map(|route| {
+			let (filter, kafka_topic) = match route.split_once('=') {
+				Some((filter, kafka_topic)) => (filter.trim(), kafka_topic.trim()),
+				None => (route, default_topic),
+			};
+
+			validate_filter(filter).map_err(|e| format!("invalid route {}: {}", route, e))?;
+			if kafka_topic.is_empty() {
+				return Err(format!("invalid route {}: the Kafka topic must not be empty", route));
+			}
+
+			Ok(Route { filter: filter.to_owned(), kafka_topic: kafka_topic.to_owned() })
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	if routes.is_empty() {
+		return Err("at least one route is required".to_owned());
+	}
+	Ok(routes)
+}
+
+impl KeyMode {
+	// Keying by device keeps the messages of a device in one partition, and so in order
+	pub fn key(self, topic: &str, payload: &str) -> Option<String> {
+		match self {
+			KeyMode::DeviceId => device_id(payload).or_else(|| Some(topic.to_owned())),
+			KeyMode::Topic => Some(topic.to_owned()),
+			KeyMode::None => None,
+		}
+	}
+}
+
+impl FromStr for KeyMode {
+	type Err = String;
+
+	fn from_str(mode: &str) -> Result<Self, Self::Err> {
+		match mode {
+			"device_id" => Ok(KeyMode::DeviceId),
+			"topic" => Ok(KeyMode::Topic),
+			"none" => Ok(KeyMode::None),
+			_ => Err(format!("unknown key mode {}, expected device_id, topic or none", mode)),
+		}
+	}
+}
+
+fn device_id(payload: &str) -> Option<String> {
+	let message: serde_json::Value = serde_json::from_str(payload).ok()?;
+	message.get("device_id")?.as_str().map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_routes() {
+		let routes = parse_routes("sensors, devices/+/status=iot-status ,devices/#={topic}", "iot-readings").unwrap();
+
+		assert_eq!(routes.len(), 3);
+		assert_eq!(routes[0].kafka_topic("sensors"), "iot-readings");
+		assert!(routes[1].matches("devices/station-1/status"));
+		assert_eq!(routes[1].kafka_topic("devices/station-1/status"), "iot-status");
+		assert_eq!(routes[2].kafka_topic("devices/station-1/responses"), "devices.station-1.responses");
+
+		assert_eq!(parse_routes(" , ", "iot-readings"), Err("at least one route is required".to_owned()));
+		assert!(parse_routes("devices/#/status", "iot-readings").is_err());
+		assert!(parse_routes("sensors=", "iot-readings").is_err());
+	}
+
+	#[test]
+	fn test_message_keys() {
+		let payload = r#"{"device_id": "station-1", "sensor": "SPS30", "value": 0.69}"#;
+
+		assert_eq!(KeyMode::DeviceId.key("sensors", payload).as_deref(), Some("station-1"));
+		assert_eq!(KeyMode::DeviceId.key("devices/station-1/raw", "0.69").as_deref(), Some("devices/station-1/raw"));
+		assert_eq!(KeyMode::Topic.key("sensors", payload).as_deref(), Some("sensors"));
+		assert_eq!(KeyMode::None.key("sensors", payload), None);
+		assert_eq!("topic".parse(), Ok(KeyMode::Topic));
+		assert!("partition".parse::<KeyMode>().is_err());
+	}
+}
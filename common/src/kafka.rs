@@ -0,0 +1,278 @@
+use crate::message::Origin;
+use crate::utils::dotenv;
+use chrono::{DateTime, Utc};
+use rdkafka::config::ClientConfig;
+use rdkafka::config::RDKafkaLogLevel;
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::error::KafkaError;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::{Message, Offset, TopicPartitionList};
+use std::error::Error;
+use std::future::Future;
+use std::time::Duration;
+pub struct Kafka {
+	consumer: Option<StreamConsumer>,
+	producer: FutureProducer,
+}
+
+pub struct KafkaMessage {
+	pub payload: Vec<u8>,
+	pub key: Option<Vec<u8>>,
+	pub topic: String,
+	pub partition: i32,
+	pub offset: i64,
+	pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl Kafka {
+	pub fn new() -> Result<Self, Box<dyn Error>> {
+		Self::with_group_id(&dotenv::get_var("KAFKA_GROUP_ID").unwrap())
+	}
+
+	pub fn with_group_id(group_id: &str) -> Result<Self, Box<dyn Error>> {
+		let brokers = dotenv::get_var("KAFKA_BROKER").unwrap();
+
+		let consumer: StreamConsumer = client_config(&brokers)
+			.set("group.id", group_id)
+			.set("enable.partition.eof", "false")
+			.set("session.timeout.ms", "6000")
+			.set("enable.auto.commit", "false")
+			.set_log_level(RDKafkaLogLevel::Debug)
+			.create()?;
+
+		Ok(Kafka { consumer: Some(consumer), producer: create_producer(&brokers)? })
+	}
+
+	// For clients that only forward messages to Kafka, like the bridge, which need no consumer group
+	pub fn producer() -> Result<Self, Box<dyn Error>> {
+		let brokers = dotenv::get_var("KAFKA_BROKER")?;
+
+		Ok(Kafka { consumer: None, producer: create_producer(&brokers)? })
+	}
+
+	pub fn subscribe(&self, topic: &str) -> Result<(), KafkaError> {
+		self.consumer()?.subscribe(&[topic])
+	}
+
+	pub async fn consume<F, Fut>(&self, mut callback: F) -> Result<(), Box<dyn Error>>
+	where
+		F: FnMut(&str) -> Fut,
+		Fut: Future<Output = Result<(), Box<dyn Error>>>,
+	{
+		loop {
+			let message = self.receive().await?;
+			match std::str::from_utf8(&message.payload) {
+				Ok(payload) => callback(payload).await?,
+				Err(e) => println!("Error while deserializing message payload: {}", e),
+			}
+			self.commit(&message)?;
+		}
+	}
+
+	pub async fn receive(&self) -> Result<KafkaMessage, KafkaError> {
+		let message = self.consumer()?.recv().await?;
+
+		Ok(KafkaMessage {
+			payload: message.payload().unwrap_or_default().to_vec(),
+			key: message.key().map(<[u8]>::to_vec),
+			topic: message.topic().to_owned(),
+			partition: message.partition(),
+			offset: message.offset(),
+			timestamp: message.timestamp().to_millis().and_then(DateTime::from_timestamp_millis),
+		})
+	}
+
+	// Offsets are only committed once a message is handled, so unhandled messages are consumed again after a restart
+	pub fn commit(&self, message: &KafkaMessage) -> Result<(), KafkaError> {
+		self.commit_until(&message.topic, message.partition, message.offset + 1)
+	}
+
+	// Commits the messages of the partition before the offset, which is the next one read after a restart
+	pub fn commit_until(&self, topic: &str, partition: i32, offset: i64) -> Result<(), KafkaError> {
+		self.commit_offset(topic, partition, offset, CommitMode::Async)
+	}
+
+	// Waits for the broker to store the offset, for the last commit before stopping, which an async commit could lose
+	pub fn commit_until_sync(&self, topic: &str, partition: i32, offset: i64) -> Result<(), KafkaError> {
+		self.commit_offset(topic, partition, offset, CommitMode::Sync)
+	}
+
+	fn commit_offset(&self, topic: &str, partition: i32, offset: i64, mode: CommitMode) -> Result<(), KafkaError> {
+		let mut offsets = TopicPartitionList::new();
+		offsets.add_partition_offset(topic, partition, Offset::Offset(offset))?;
+
+		self.consumer()?.commit(&offsets, mode)
+	}
+
+	fn consumer(&self) -> Result<&StreamConsumer, KafkaError> {
+		self.consumer.as_ref().ok_or_else(|| KafkaError::Subscription("this Kafka client only produces".to_owned()))
+	}
+
+	pub async fn produce(&self, topic: &str, payload: &str) -> Result<(), KafkaError> {
+		self.produce_with_key(topic, Some("sensors"), payload.as_bytes()).await
+	}
+
+	pub async fn produce_with_key(&self, topic: &str, key: Option<&str>, payload: &[u8]) -> Result<(), KafkaError> {
+		let mut record = FutureRecord::to(topic).payload(payload);
+		if let Some(key) = key {
+			record = record.key(key);
+		}
+
+		match self.producer.send(record, Duration::from_secs(0)).await {
+			Ok(_) => Ok(()),
+			Err((error, _)) => Err(error),
+		}
+	}
+}
+
+impl KafkaMessage {
+	pub fn origin(&self) -> Origin {
+		Origin {
+			topic: Some(self.topic.clone()),
+			partition: Some(self.partition),
+			offset: Some(self.offset),
+			timestamp: self.timestamp,
+		}
+	}
+}
+
+// Idempotence keeps the retries of the producer from writing duplicates or reordering messages
+fn create_producer(brokers: &str) -> Result<FutureProducer, KafkaError> {
+	client_config(brokers).set("enable.idempotence", "true").set("acks", "all").create()
+}
+
+// SASL is only configured when KAFKA_USERNAME is set, so a local broker without authentication also works
+fn client_config(brokers: &str) -> ClientConfig {
+	let username = dotenv::parse_var::<String>("KAFKA_USERNAME");
+	let default_protocol = if username.is_some() { "sasl_ssl" } else { "plaintext" };
+
+	let mut config = ClientConfig::new();
+	config
+		.set("bootstrap.servers", brokers)
+		.set("security.protocol", dotenv::parse_var("KAFKA_SECURITY_PROTOCOL").unwrap_or(default_protocol.to_owned()));
+
+	if let Some(username) = username {
+		config
+			.set("sasl.mechanisms", dotenv::parse_var("KAFKA_SASL_MECHANISM").unwrap_or("SCRAM-SHA-256".to_owned()))
+			.set("sasl.username", username)
+			.set("sasl.password", dotenv::parse_var::<String>("KAFKA_PASSWORD").unwrap_or_default());
+	}
+
+	config
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn test_integrity() {
+		let kafka = Kafka::new().expect("Failed to create Kafka client");
+		let topic = dotenv::get_var("KAFKA_TOPIC_TESTS").unwrap();
+		let test_message = "Hello NicoNicoNii";
+
+		kafka.subscribe(&topic).expect("Failed to subscribe to topic");
+
+		tokio::time::sleep(Duration::from_secs(1)).await;
+
+		kafka.produce(&topic, test_message).await.expect("Failed to produce message");
+
+		let mut consumed_message = None;
+
+		let consume_future = kafka.consume(|payload| {
+			consumed_message = Some(payload.to_owned());
+			async move { Ok(()) }
+		});
+
+		let consume_timeout = tokio::time::timeout(Duration::from_secs(5), consume_future);
+		if let Err(_) = consume_timeout.await {
+			panic!("Consuming timed out without receiving a message");
+		}
+
+		kafka.consumer().unwrap().unsubscribe();
+
+		assert_eq!(
+			consumed_message.map(|s| s.to_owned()),
+			Some(test_message.to_owned()),
+			"Consumed message does not match produced message"
+		);
+	}
+
+	#[tokio::test]
+	async fn test_produce() {
+		let kafka = Kafka::new().expect("Failed to create Kafka client");
+		let topic = dotenv::get_var("KAFKA_TOPIC_TESTS").unwrap();
+		let test_message = "Hello NicoNicoNii";
+
+		kafka.produce(&topic, test_message).await.expect("Failed to produce message");
+	}
+
+	#[tokio::test]
+	async fn test_consume() {
+		let kafka = Kafka::new().expect("Failed to create Kafka client");
+		let topic = dotenv::get_var("KAFKA_TOPIC_TESTS").unwrap();
+		let test_message = "Hello NicoNicoNii";
+
+		kafka.subscribe(&topic).expect("Failed to subscribe to topic");
+
+		tokio::time::sleep(Duration::from_secs(1)).await;
+
+		kafka.produce(&topic, test_message).await.expect("Failed to produce message");
+
+		let mut consumed_message = None;
+
+		kafka
+			.consume(|payload| {
+				consumed_message = Some(payload.to_owned());
+				async move { Ok(()) }
+			})
+			.await
+			.expect("Failed to consume message");
+
+		kafka.consumer().unwrap().unsubscribe();
+
+		assert_eq!(
+			consumed_message.map(|s| s.to_owned()),
+			Some(test_message.to_owned()),
+			"Consumed message does not match produced message"
+		);
+	}
+
+	#[tokio::test]
+	async fn test_producer() {
+		let kafka = Kafka::producer().expect("Failed to create Kafka producer");
+		let topic = dotenv::get_var("KAFKA_TOPIC_TESTS").unwrap();
+
+		kafka.produce(&topic, "Hello NicoNicoNii").await.expect("Failed to produce message");
+		assert!(kafka.subscribe(&topic).is_err(), "A producer should not consume");
+	}
+
+	#[tokio::test]
+	async fn test_binary_payload() {
+		let kafka = Kafka::new().expect("Failed to create Kafka client");
+		let topic = dotenv::get_var("KAFKA_TOPIC_TESTS").unwrap();
+		let payload = [0x00, 0x9f, 0xff, 0x42];
+
+		kafka.subscribe(&topic).expect("Failed to subscribe to topic");
+
+		tokio::time::sleep(Duration::from_secs(1)).await;
+
+		kafka.produce_with_key(&topic, None, &payload).await.expect("Failed to produce message");
+		let message = tokio::time::timeout(Duration::from_secs(5), kafka.receive())
+			.await
+			.expect("Receiving timed out")
+			.expect("Failed to receive message");
+
+		kafka.consumer().unwrap().unsubscribe();
+
+		assert_eq!(message.payload, payload, "Binary payloads should be produced unchanged");
+	}
+
+	#[tokio::test]
+	async fn test_subscribe() {
+		let kafka = Kafka::new().expect("Failed to create Kafka client");
+		let topic = dotenv::get_var("KAFKA_TOPIC_TESTS").unwrap();
+
+		kafka.subscribe(&topic).expect("Failed to subscribe to topic");
+	}
+}
@@ -1,2 +1,5 @@
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod message;
 pub mod mqtt;
 pub mod utils;
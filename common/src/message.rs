@@ -0,0 +1,313 @@
+use chrono::{DateTime, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{error::Error, fmt};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SensorMessage {
+	#[serde(default)]
+	pub version: u32,
+	#[serde(default)]
+	pub device_id: String,
+	pub sensor: String,
+	#[serde(serialize_with = "finite_or_string", deserialize_with = "number_or_string")]
+	pub value: f64,
+	pub unit: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timestamp: Option<DateTime<Utc>>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub sequence: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Availability {
+	Online,
+	Offline,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusMessage {
+	pub device_id: String,
+	pub status: Availability,
+}
+
+// Where a message was read from, as far as its source knows
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Origin {
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub topic: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub partition: Option<i32>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub offset: Option<i64>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadEncoding {
+	#[default]
+	Utf8,
+	Hex,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetter {
+	pub error: String,
+	pub payload: String,
+	#[serde(default, skip_serializing_if = "PayloadEncoding::is_utf8")]
+	pub encoding: PayloadEncoding,
+	#[serde(flatten)]
+	pub origin: Origin,
+	pub failed_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+pub enum MessageError {
+	Json(serde_json::Error),
+	UnsupportedVersion(u32),
+	InvalidValue(f64),
+}
+
+impl SensorMessage {
+	pub fn new(device_id: &str, sensor: &str, value: f64, unit: &str, timestamp: DateTime<Utc>) -> Self {
+		SensorMessage {
+			version: SCHEMA_VERSION,
+			device_id: device_id.to_owned(),
+			sensor: sensor.to_owned(),
+			value,
+			unit: unit.to_owned(),
+			timestamp: Some(timestamp),
+			sequence: None,
+		}
+	}
+
+	pub fn with_sequence(mut self, sequence: u64) -> Self {
+		self.sequence = Some(sequence);
+		self
+	}
+
+	pub fn decode(payload: &str) -> Result<Self, MessageError> {
+		let message: SensorMessage = serde_json::from_str(payload).map_err(MessageError::Json)?;
+
+		if message.version > SCHEMA_VERSION {
+			return Err(MessageError::UnsupportedVersion(message.version));
+		}
+
+		if !message.value.is_finite() {
+			return Err(MessageError::InvalidValue(message.value));
+		}
+
+		Ok(message)
+	}
+
+	pub fn encode(&self) -> String {
+		serde_json::to_string(self).expect("SensorMessage serialization is infallible")
+	}
+
+	pub fn is_legacy(&self) -> bool {
+		self.version == 0
+	}
+}
+
+impl StatusMessage {
+	pub fn new(device_id: &str, status: Availability) -> Self {
+		StatusMessage { device_id: device_id.to_owned(), status }
+	}
+
+	pub fn decode(payload: &str) -> Result<Self, MessageError> {
+		serde_json::from_str(payload).map_err(MessageError::Json)
+	}
+
+	pub fn encode(&self) -> String {
+		serde_json::to_string(self).expect("StatusMessage serialization is infallible")
+	}
+}
+
+impl PayloadEncoding {
+	fn is_utf8(&self) -> bool {
+		*self == PayloadEncoding::Utf8
+	}
+}
+
+impl DeadLetter {
+	// Payloads that are not UTF-8 are kept as hex, so they can be re-driven unchanged
+	pub fn new(payload: &[u8], error: &str, origin: Origin) -> Self {
+		let (payload, encoding) = match std::str::from_utf8(payload) {
+			Ok(payload) => (payload.to_owned(), PayloadEncoding::Utf8),
+			Err(_) => (payload.iter().map(|byte| format!("{:02x}", byte)).collect(), PayloadEncoding::Hex),
+		};
+
+		DeadLetter { error: error.to_owned(), payload, encoding, origin, failed_at: Utc::now() }
+	}
+
+	// The original payload, None when a hex payload is corrupted
+	pub fn payload_bytes(&self) -> Option<Vec<u8>> {
+		match self.encoding {
+			PayloadEncoding::Utf8 => Some(self.payload.as_bytes().to_vec()),
+			PayloadEncoding::Hex if self.payload.len() % 2 == 0 && self.payload.bytes().all(|c| c.is_ascii_hexdigit()) => {
+				(0..self.payload.len()).step_by(2).map(|i| u8::from_str_radix(&self.payload[i..i + 2], 16).ok()).collect()
+			}
+			PayloadEncoding::Hex => None,
+		}
+	}
+
+	pub fn decode(payload: &str) -> Result<Self, MessageError> {
+		serde_json::from_str(payload).map_err(MessageError::Json)
+	}
+
+	pub fn encode(&self) -> String {
+		serde_json::to_string(self).expect("DeadLetter serialization is infallible")
+	}
+}
+
+impl fmt::Display for Availability {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Availability::Online => write!(f, "online"),
+			Availability::Offline => write!(f, "offline"),
+		}
+	}
+}
+
+fn number_or_string<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum Value {
+		Number(f64),
+		String(String),
+	}
+
+	match Value::deserialize(deserializer)? {
+		Value::Number(value) => Ok(value),
+		Value::String(value) => value.trim().parse::<f64>().map_err(de::Error::custom),
+	}
+}
+
+// JSON has no NaN or infinity, so they are written as the strings number_or_string reads back
+fn finite_or_string<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	if value.is_finite() {
+		serializer.serialize_f64(*value)
+	} else {
+		serializer.serialize_str(&value.to_string())
+	}
+}
+
+impl fmt::Display for MessageError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			MessageError::Json(e) => write!(f, "invalid sensor message: {}", e),
+			MessageError::UnsupportedVersion(version) => {
+				write!(f, "unsupported sensor message version {}, expected at most {}", version, SCHEMA_VERSION)
+			}
+			MessageError::InvalidValue(value) => write!(f, "invalid sensor message value {}", value),
+		}
+	}
+}
+
+impl Error for MessageError {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_message_round_trip() {
+		let timestamp = DateTime::parse_from_rfc3339("2024-01-01T12:00:00.250Z").unwrap().with_timezone(&Utc);
+		let message = SensorMessage::new("station-1", "SPS30", 0.69, "μg/m³", timestamp).with_sequence(42);
+		let payload = message.encode();
+
+		assert_eq!(
+			payload,
+			r#"{"version":1,"device_id":"station-1","sensor":"SPS30","value":0.69,"unit":"μg/m³","timestamp":"2024-01-01T12:00:00.250Z","sequence":42}"#
+		);
+		assert_eq!(SensorMessage::decode(&payload).expect("Failed to decode message"), message);
+	}
+
+	#[test]
+	fn test_non_finite_value() {
+		let timestamp = DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z").unwrap().with_timezone(&Utc);
+
+		for (value, encoded) in [(f64::NAN, r#""value":"NaN""#), (f64::NEG_INFINITY, r#""value":"-inf""#)] {
+			let payload = SensorMessage::new("station-1", "SPS30", value, "μg/m³", timestamp).encode();
+			assert!(payload.contains(encoded), "Unexpected payload: {}", payload);
+
+			let error = SensorMessage::decode(&payload).expect_err("Non-finite values should be rejected");
+			assert!(matches!(error, MessageError::InvalidValue(decoded) if decoded.to_string() == value.to_string()));
+		}
+	}
+
+	#[test]
+	fn test_legacy_message() {
+		let message = SensorMessage::decode(r#"{"sensor":"SPS30","value":"0.52","unit":"μg/m³"}"#).expect("Failed to decode message");
+
+		assert!(message.is_legacy());
+		assert_eq!(message.sensor, "SPS30");
+		assert_eq!(message.value, 0.52);
+		assert_eq!(message.unit, "μg/m³");
+		assert_eq!(message.device_id, "");
+		assert_eq!(message.timestamp, None);
+		assert_eq!(message.sequence, None);
+	}
+
+	#[test]
+	fn test_status_message() {
+		let status = StatusMessage::new("station-1", Availability::Offline);
+
+		assert_eq!(status.encode(), r#"{"device_id":"station-1","status":"offline"}"#);
+		assert_eq!(StatusMessage::decode(&status.encode()).expect("Failed to decode status"), status);
+		assert!(StatusMessage::decode(&SensorMessage::new("station-1", "SPS30", 0.5, "μg/m³", Utc::now()).encode()).is_err());
+	}
+
+	#[test]
+	fn test_dead_letter() {
+		let timestamp = DateTime::parse_from_rfc3339("2024-01-01T12:00:00Z").unwrap().with_timezone(&Utc);
+		let origin =
+			Origin { topic: Some("sensors".to_owned()), partition: Some(2), offset: Some(17), timestamp: Some(timestamp) };
+		let mut letter = DeadLetter::new(br#"{"sensor":"SPS30""#, "invalid sensor message", origin);
+		letter.failed_at = timestamp;
+
+		assert_eq!(
+			letter.encode(),
+			r#"{"error":"invalid sensor message","payload":"{\"sensor\":\"SPS30\"","topic":"sensors","partition":2,"offset":17,"timestamp":"2024-01-01T12:00:00Z","failed_at":"2024-01-01T12:00:00Z"}"#
+		);
+		assert_eq!(DeadLetter::decode(&letter.encode()).expect("Failed to decode dead letter"), letter);
+
+		let mut letter = DeadLetter::new(&[0x00, 0x9f, 0xff], "invalid utf-8 sequence", Origin::default());
+		letter.failed_at = timestamp;
+		assert_eq!(
+			letter.encode(),
+			r#"{"error":"invalid utf-8 sequence","payload":"009fff","encoding":"hex","failed_at":"2024-01-01T12:00:00Z"}"#
+		);
+		let decoded = DeadLetter::decode(&letter.encode()).expect("Failed to decode dead letter");
+		assert_eq!(decoded.payload_bytes(), Some(vec![0x00, 0x9f, 0xff]), "Binary payloads should be kept unchanged");
+
+		letter.payload = "9+".to_owned();
+		assert_eq!(letter.payload_bytes(), None);
+	}
+
+	#[test]
+	fn test_invalid_messages() {
+		let cases = [
+			(r#"{"sensor":"SPS30","value":"high","unit":"μg/m³"}"#, "invalid sensor message"),
+			(r#"{"sensor":"SPS30","value":null,"unit":"μg/m³"}"#, "invalid sensor message"),
+			(r#"{"sensor":"SPS30","value":0.5"#, "invalid sensor message"),
+			(r#"{"version":2,"sensor":"SPS30","value":0.5,"unit":"μg/m³"}"#, "unsupported sensor message version 2"),
+			(r#"{"sensor":"SPS30","value":"NaN","unit":"μg/m³"}"#, "invalid sensor message value NaN"),
+		];
+
+		for (payload, error) in cases {
+			let result = SensorMessage::decode(payload).expect_err("Message should be invalid");
+			assert!(result.to_string().starts_with(error), "Unexpected error: {}", result);
+		}
+	}
+}
@@ -1,102 +1,385 @@
+mod async_client;
+mod properties;
+mod queue;
+mod reconnect;
+mod router;
+mod tls;
+
+pub use async_client::{AsyncMqttClient, ClientOptions};
+pub use properties::PublishProperties;
+pub use queue::{OfflineQueue, QueuedMessage};
+pub use reconnect::ReconnectOptions;
+pub use router::{topic_matches, validate_filter, TopicRouter};
+pub use paho_mqtt::{Error, Message};
+pub use tls::{TlsOptions, Transport};
+
+use crate::message::{Availability, StatusMessage};
 use crate::utils::dotenv;
 use mqtt::Receiver;
 use paho_mqtt as mqtt;
-use std::time::{Duration, Instant};
+use std::{
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+
+const STATUS_QOS: i32 = 1;
+
+pub type Handler = Box<dyn FnMut(&mqtt::Message) + Send>;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum MqttVersion {
+	#[default]
+	V3,
+	V5,
+}
+
+impl MqttVersion {
+	pub fn from_number(version: u8) -> Option<Self> {
+		match version {
+			3 => Some(MqttVersion::V3),
+			5 => Some(MqttVersion::V5),
+			_ => None,
+		}
+	}
+
+	pub fn from_env() -> Self {
+		dotenv::parse_var("MQTT_VERSION").and_then(Self::from_number).unwrap_or_default()
+	}
+}
 
 pub struct MqttClient {
 	client: mqtt::Client,
+	consumer: Receiver<Option<mqtt::Message>>,
 	broker: String,
+	client_id: String,
+	version: MqttVersion,
+	session_expiry: Option<u32>,
+	status_topic: Option<String>,
+	subscriptions: Mutex<Vec<(String, i32)>>,
+	retry: Mutex<(u32, Option<Instant>)>,
+	handlers: Mutex<TopicRouter<Handler>>,
 }
 
 impl MqttClient {
 	pub fn new(client_id: &str) -> Result<Self, mqtt::Error> {
-		let broker = dotenv::get_var("BROKER").unwrap();
-		let create_opts = mqtt::CreateOptionsBuilder::new().server_uri(broker.clone()).client_id(client_id).finalize();
+		Self::with_version(client_id, MqttVersion::from_env(), dotenv::parse_var("MQTT_SESSION_EXPIRY"))
+	}
+
+	pub fn with_version(client_id: &str, version: MqttVersion, session_expiry: Option<u32>) -> Result<Self, mqtt::Error> {
+		let (broker, create_opts) = create_options(client_id, version)?;
 
 		let client = mqtt::Client::new(create_opts)?;
-		Ok(MqttClient { client, broker })
+		let consumer = client.start_consuming();
+		Ok(MqttClient {
+			client,
+			consumer,
+			broker,
+			client_id: client_id.to_owned(),
+			version,
+			session_expiry,
+			status_topic: status_topic_from_env(client_id),
+			subscriptions: Mutex::new(Vec::new()),
+			retry: Mutex::new((0, None)),
+			handlers: Mutex::new(TopicRouter::new()),
+		})
 	}
 
-	pub fn connect(&mut self, keep_alive_interval: Duration, clean_session: bool) -> Result<(), mqtt::Error> {
-		let mut ssl_opts_builder = mqtt::SslOptionsBuilder::new();
-		let ssl = ssl_opts_builder.enable_server_cert_auth(true).finalize();
+	// The status topic gets a retained "online" message on connect, and "offline" on disconnect or as the last will
+	pub fn with_status_topic(mut self, topic: &str) -> Self {
+		self.status_topic = Some(topic.replace("{id}", &self.client_id));
+		self
+	}
 
-		let mut conn_opts_builder = mqtt::ConnectOptionsBuilder::new();
-		conn_opts_builder.keep_alive_interval(keep_alive_interval).clean_session(clean_session).ssl_options(ssl);
+	// For clients that are not devices, like the bridge, which should not show up as devices in the status topics
+	pub fn without_status_topic(mut self) -> Self {
+		self.status_topic = None;
+		self
+	}
 
-		if let (Ok(username), Ok(password)) = (dotenv::get_var("BROKER_USERNAME"), dotenv::get_var("BROKER_PASSWORD")) {
-			conn_opts_builder.user_name(username).password(password);
+	// Returns whether the broker resumed a previous session, in which case the subscriptions are still in place
+	pub fn connect(&mut self, keep_alive_interval: Duration, clean_session: bool) -> Result<bool, mqtt::Error> {
+		let will = self.status(Availability::Offline);
+		let options = connect_options(&self.broker, keep_alive_interval, clean_session, self.version, self.session_expiry, will)?;
+		let session_present = session_present(&self.client.connect(options)?);
+		if session_present {
+			println!("Resumed the session on the broker at {}", self.broker);
+		} else {
+			println!("Connected to the broker at {}", self.broker);
 		}
 
-		let conn_opts = conn_opts_builder.finalize();
+		if let Some(online) = self.status(Availability::Online) {
+			self.client.publish(online)?;
+		}
 
-		self.client.connect(conn_opts)?;
-		println!("Connected to the broker at {}", self.broker);
+		Ok(session_present)
+	}
+
+	pub fn is_connected(&self) -> bool {
+		self.client.is_connected()
+	}
+
+	// Subscribes again, as the broker only keeps the subscriptions of sessions that are not clean and have not expired
+	pub fn reconnect(&self) -> Result<(), mqtt::Error> {
+		self.client.reconnect()?;
+		println!("Reconnected to the broker at {}", self.broker);
+
+		if let Some(online) = self.status(Availability::Online) {
+			self.client.publish(online)?;
+		}
+
+		let subscriptions = self.subscriptions.lock().unwrap().clone();
+		for (topic, qos) in subscriptions {
+			self.client.subscribe(&topic, qos)?;
+		}
 
 		Ok(())
 	}
 
+	// Tries to reconnect when the connection is lost, waiting for the backoff delay between failed attempts
+	pub fn ensure_connected(&self, options: &ReconnectOptions) -> bool {
+		if self.client.is_connected() {
+			return true;
+		}
+
+		let mut retry = self.retry.lock().unwrap();
+		if retry.1.is_some_and(|retry_at| Instant::now() < retry_at) {
+			return false;
+		}
+
+		if let Err(e) = self.reconnect() {
+			let delay = options.delay(retry.0, &mut rand::thread_rng());
+			*retry = (retry.0.saturating_add(1), Some(Instant::now() + delay));
+
+			eprintln!("Failed to reconnect to the broker at {}, retrying in {:.1}s: {}", self.broker, delay.as_secs_f64(), e);
+			return false;
+		}
+
+		*retry = (0, None);
+		true
+	}
+
 	pub fn publish(&self, topic: &str, payload: &str, qos: i32) -> Result<(), mqtt::Error> {
-		let msg = mqtt::MessageBuilder::new().topic(topic).payload(payload).qos(qos).finalize();
+		self.publish_with(topic, payload, qos, &PublishProperties::default())
+	}
 
-		self.client.publish(msg)
+	pub fn publish_with(&self, topic: &str, payload: &str, qos: i32, properties: &PublishProperties) -> Result<(), mqtt::Error> {
+		self.client.publish(build_message(topic, payload, qos, properties, self.version)?)
 	}
 
-	pub fn subscribe(&self, topic: &str, qos: i32) -> Result<(), mqtt::Error> {
-		self.client.subscribe(topic, qos).map_err(|e| e)?;
-		Ok(())
+	// Returns the QoS granted by the broker, which may be lower than the requested one
+	pub fn subscribe(&self, topic: &str, qos: i32) -> Result<i32, mqtt::Error> {
+		let granted = granted_qos(&self.client.subscribe(topic, qos)?, qos)?;
+
+		let mut subscriptions = self.subscriptions.lock().unwrap();
+		subscriptions.retain(|(subscribed, _)| subscribed != topic);
+		subscriptions.push((topic.to_owned(), qos));
+		Ok(granted)
 	}
 
 	pub fn unsubscribe(&self, topic: &str) -> Result<(), mqtt::Error> {
 		self.client.unsubscribe(topic).map_err(|e| e)?;
+
+		self.subscriptions.lock().unwrap().retain(|(subscribed, _)| subscribed != topic);
 		Ok(())
 	}
 
 	pub fn disconnect(&self) -> Result<(), mqtt::Error> {
+		if let Some(offline) = self.status(Availability::Offline) {
+			self.client.publish(offline)?;
+		}
+
 		self.client.disconnect(None)
 	}
 
-	pub fn start_consuming(&self) -> Receiver<Option<mqtt::Message>> {
-		self.client.start_consuming()
+	pub fn messages(&self) -> Receiver<Option<mqtt::Message>> {
+		self.consumer.clone()
 	}
 
-	pub fn collect_messages(&self, duration: Duration) -> Vec<String> {
-		let rx = self.start_consuming();
-		let end_time = Instant::now() + duration;
+	pub fn collect_messages(&self, duration: Duration) -> Vec<mqtt::Message> {
+		let deadline = Instant::now() + duration;
 
 		let mut messages = Vec::new();
-		while Instant::now() < end_time {
-			if let Ok(Some(message)) = rx.try_recv() {
-				messages.push(message.payload_str().to_string());
-			}
+		while let Some(message) = self.receive_until(deadline) {
+			messages.push(message);
 		}
 		messages
 	}
 
-	pub fn wait_for_message(&self, duration: Duration) -> Option<String> {
-		let rx = self.start_consuming();
-		let end_time = Instant::now() + duration;
-
-		while Instant::now() < end_time {
-			if let Ok(Some(message)) = rx.try_recv() {
-				return Some(message.payload_str().to_string());
-			}
-		}
-		None
+	pub fn wait_for_message(&self, timeout: Duration) -> Option<mqtt::Message> {
+		self.receive_until(Instant::now() + timeout)
 	}
 
 	pub fn measure_rate(&self, duration: Duration) -> (usize, Duration) {
-		let rx = self.start_consuming();
 		let start_time = Instant::now();
+
 		let mut message_count = 0;
+		while self.receive_until(start_time + duration).is_some() {
+			message_count += 1;
+		}
+		(message_count, start_time.elapsed())
+	}
+
+	// Handlers run while the routes are locked, so they can not add or remove routes themselves
+	pub fn route<F>(&self, filter: &str, qos: i32, handler: F) -> Result<(), mqtt::Error>
+	where
+		F: FnMut(&mqtt::Message) + Send + 'static,
+	{
+		validate_filter(filter).map_err(mqtt::Error::General)?;
+		self.subscribe(filter, qos)?;
+		self.handlers.lock().unwrap().add(filter, Box::new(handler)).map_err(mqtt::Error::General)
+	}
+
+	pub fn unroute(&self, filter: &str) -> Result<(), mqtt::Error> {
+		self.handlers.lock().unwrap().remove(filter);
+		self.unsubscribe(filter)
+	}
+
+	pub fn dispatch(&self, duration: Duration) -> usize {
+		let deadline = Instant::now() + duration;
+
+		let mut handled = 0;
+		while let Some(message) = self.receive_until(deadline) {
+			if self.handle(&message) {
+				handled += 1;
+			} else {
+				eprintln!("No handler for the message received on {}", message.topic());
+			}
+		}
+		handled
+	}
+
+	pub fn handle(&self, message: &mqtt::Message) -> bool {
+		handle_message(&self.handlers, message)
+	}
+
+	fn receive_until(&self, deadline: Instant) -> Option<mqtt::Message> {
+		loop {
+			let timeout = deadline.checked_duration_since(Instant::now())?;
+
+			match self.consumer.recv_timeout(timeout) {
+				Ok(Some(message)) => return Some(message),
+				// None is sent when the connection is lost, keep waiting for the reconnection until the deadline
+				Ok(None) => continue,
+				Err(_) => return None,
+			}
+		}
+	}
+
+	fn status(&self, status: Availability) -> Option<mqtt::Message> {
+		self.status_topic.as_deref().map(|topic| status_message(topic, &self.client_id, status))
+	}
+}
+
+fn create_options(client_id: &str, version: MqttVersion) -> Result<(String, mqtt::CreateOptions), mqtt::Error> {
+	let broker = dotenv::get_var("BROKER").map_err(|_| mqtt::Error::General("BROKER is not set"))?;
+	if Transport::from_uri(&broker).is_none() {
+		return Err(mqtt::Error::General("Unsupported broker scheme, expected tcp, mqtt, ssl, mqtts, ws or wss"));
+	}
 
-		while Instant::now() - start_time < duration {
-			if let Ok(Some(_)) = rx.try_recv() {
-				message_count += 1;
+	let mqtt_version = match version {
+		MqttVersion::V3 => mqtt::MQTT_VERSION_3_1_1,
+		MqttVersion::V5 => mqtt::MQTT_VERSION_5,
+	};
+	let create_opts =
+		mqtt::CreateOptionsBuilder::new().server_uri(broker.clone()).client_id(client_id).mqtt_version(mqtt_version).finalize();
+
+	Ok((broker, create_opts))
+}
+
+fn connect_options(
+	broker: &str,
+	keep_alive_interval: Duration,
+	clean_session: bool,
+	version: MqttVersion,
+	session_expiry: Option<u32>,
+	will: Option<mqtt::Message>,
+) -> Result<mqtt::ConnectOptions, mqtt::Error> {
+	let mut conn_opts_builder = match version {
+		MqttVersion::V3 => {
+			let mut builder = mqtt::ConnectOptionsBuilder::new();
+			builder.clean_session(clean_session);
+			builder
+		}
+		MqttVersion::V5 => {
+			let mut builder = mqtt::ConnectOptionsBuilder::new_v5();
+			builder.clean_start(clean_session);
+			if let Some(expiry) = session_expiry {
+				let mut properties = mqtt::Properties::new();
+				properties
+					.push_u32(mqtt::PropertyCode::SessionExpiryInterval, expiry)
+					.expect("Session expiry interval is a four byte integer property");
+				builder.properties(properties);
 			}
+			builder
 		}
-		(message_count, Instant::now() - start_time)
+	};
+	conn_opts_builder.keep_alive_interval(keep_alive_interval);
+
+	if let Some(will) = will {
+		conn_opts_builder.will_message(will);
+	}
+
+	if Transport::from_uri(broker).is_some_and(Transport::is_secure) {
+		conn_opts_builder.ssl_options(TlsOptions::from_env().ssl_options()?);
+	}
+
+	if let Some(username) = dotenv::parse_var::<String>("BROKER_USERNAME") {
+		conn_opts_builder.user_name(username);
+	}
+	if let Some(password) = dotenv::parse_var::<String>("BROKER_PASSWORD") {
+		conn_opts_builder.password(password);
+	}
+
+	Ok(conn_opts_builder.finalize())
+}
+
+fn build_message(
+	topic: &str,
+	payload: &str,
+	qos: i32,
+	properties: &PublishProperties,
+	version: MqttVersion,
+) -> Result<mqtt::Message, mqtt::Error> {
+	let mut builder = mqtt::MessageBuilder::new().topic(topic).payload(payload).qos(qos);
+	if version == MqttVersion::V5 && !properties.is_empty() {
+		builder = builder.properties(properties.to_properties()?);
+	}
+
+	Ok(builder.finalize())
+}
+
+fn session_present(response: &mqtt::ServerResponse) -> bool {
+	response.connect_response().is_some_and(|connect| connect.session_present)
+}
+
+// Subscribe return codes from 0x80 are failures, in MQTT v3 as well as in v5
+fn granted_qos(response: &mqtt::ServerResponse, requested: i32) -> Result<i32, mqtt::Error> {
+	match response.subscribe_response() {
+		Some(code) if code >= 0x80 => Err(mqtt::Error::General("The broker rejected the subscription")),
+		Some(granted) => Ok(granted),
+		None => Ok(requested),
+	}
+}
+
+fn handle_message(handlers: &Mutex<TopicRouter<Handler>>, message: &mqtt::Message) -> bool {
+	let mut handlers = handlers.lock().unwrap();
+
+	let mut handled = false;
+	for handler in handlers.matching(message.topic()) {
+		handler(message);
+		handled = true;
 	}
+	handled
+}
+
+fn status_message(topic: &str, client_id: &str, status: Availability) -> mqtt::Message {
+	let payload = StatusMessage::new(client_id, status).encode();
+
+	mqtt::MessageBuilder::new().topic(topic).payload(payload).qos(STATUS_QOS).retained(true).finalize()
+}
+
+fn status_topic_from_env(client_id: &str) -> Option<String> {
+	dotenv::parse_var::<String>("MQTT_STATUS_TOPIC").map(|topic| topic.replace("{id}", client_id))
 }
 
 #[cfg(test)]
@@ -142,6 +425,7 @@ mod tests {
 		let messages = client.collect_messages(sim_duration);
 		publishing_handle.join().unwrap();
 
+		assert!(messages.iter().all(|message| message.topic() == topic && message.payload_str() == "Test message"));
 		assert!(
 			messages.len() >= MESSAGE_RATE as usize * sim_duration.as_secs() as usize,
 			"Received messages: {}, Expected messages: {}",
@@ -161,15 +445,98 @@ mod tests {
 		client.subscribe(topic, 1)?;
 
 		client.publish(topic, "Test message", 0)?;
-		let received_message = client.wait_for_message(Duration::from_secs(1));
-		assert!(received_message.is_some());
-		assert_eq!(received_message.unwrap(), "Test message");
+		let received_message = client.wait_for_message(Duration::from_secs(1)).expect("Message should be received");
+		assert_eq!(received_message.payload_str(), "Test message");
+		assert_eq!(received_message.topic(), topic);
+		assert_eq!(received_message.qos(), 0);
+		assert!(!received_message.retained());
+		assert!(client.wait_for_message(Duration::from_millis(100)).is_none());
 
 		client.unsubscribe(topic)?;
 		client.disconnect()?;
 		Ok(())
 	}
 
+	#[test]
+	fn test_status_messages() -> Result<(), Box<dyn Error>> {
+		let observer = setup_mqtt_client()?;
+		let client_id = format!("{}-{}", CLIENT_ID, rand::random::<u16>());
+		let topic = format!("test_status_messages/{}", client_id);
+		observer.subscribe(&topic, 1)?;
+
+		let mut device = MqttClient::new(&client_id)?.with_status_topic(&topic);
+		device.connect(Duration::from_secs(60), true)?;
+		let online = observer.wait_for_message(Duration::from_secs(1)).expect("Online status should be published");
+		assert_eq!(online.topic(), topic);
+		assert_eq!(StatusMessage::decode(&online.payload_str())?, StatusMessage::new(&client_id, Availability::Online));
+
+		device.disconnect()?;
+		let offline = observer.wait_for_message(Duration::from_secs(1)).expect("Offline status should be published");
+		assert_eq!(StatusMessage::decode(&offline.payload_str())?, StatusMessage::new(&client_id, Availability::Offline));
+
+		observer.client.publish(mqtt::MessageBuilder::new().topic(&topic).payload("").retained(true).finalize())?;
+		observer.unsubscribe(&topic)?;
+		observer.disconnect()?;
+		Ok(())
+	}
+
+	#[test]
+	fn test_routed_handlers() -> Result<(), Box<dyn Error>> {
+		let client = setup_mqtt_client()?;
+		let commands = Arc::new(Mutex::new(Vec::new()));
+		let statuses = Arc::new(Mutex::new(Vec::new()));
+
+		let received = Arc::clone(&commands);
+		client.route("test_routed_handlers/+/commands", 1, move |message| {
+			received.lock().unwrap().push((message.topic().to_owned(), message.payload_str().to_string()))
+		})?;
+		let received = Arc::clone(&statuses);
+		client.route("$share/test/test_routed_handlers/+/status", 1, move |message| {
+			received.lock().unwrap().push(message.payload_str().to_string())
+		})?;
+
+		client.publish("test_routed_handlers/station-1/commands", "reset", 1)?;
+		client.publish("test_routed_handlers/station-1/status", "online", 1)?;
+		client.publish("test_routed_handlers/station-2/commands", "shutdown", 1)?;
+		assert_eq!(client.dispatch(Duration::from_secs(1)), 3);
+
+		assert_eq!(
+			*commands.lock().unwrap(),
+			[
+				("test_routed_handlers/station-1/commands".to_owned(), "reset".to_owned()),
+				("test_routed_handlers/station-2/commands".to_owned(), "shutdown".to_owned()),
+			]
+		);
+		assert_eq!(*statuses.lock().unwrap(), ["online"]);
+
+		client.unroute("test_routed_handlers/+/commands")?;
+		client.unroute("$share/test/test_routed_handlers/+/status")?;
+		client.disconnect()?;
+		Ok(())
+	}
+
+	#[test]
+	fn test_server_responses() -> Result<(), Box<dyn Error>> {
+		let client_id = format!("{}-{}", CLIENT_ID, rand::random::<u16>());
+		let topic = "test_server_responses";
+
+		let mut client = MqttClient::new(&client_id)?;
+		assert!(!client.connect(Duration::from_secs(60), false)?, "A new session should not be resumed");
+		assert_eq!(client.subscribe(topic, 1)?, 1);
+		assert!(client.subscribe("test_server_responses/#/invalid", 1).is_err(), "Invalid filters should be rejected");
+		client.disconnect()?;
+
+		let mut client = MqttClient::new(&client_id)?;
+		assert!(client.connect(Duration::from_secs(60), false)?, "The session should be resumed");
+		client.unsubscribe(topic)?;
+		client.disconnect()?;
+
+		let mut client = MqttClient::new(&client_id)?;
+		assert!(!client.connect(Duration::from_secs(60), true)?, "A clean session should not be resumed");
+		client.disconnect()?;
+		Ok(())
+	}
+
 	#[test]
 	fn test_measure_rate() -> Result<(), Box<dyn Error>> {
 		let client = Arc::new(setup_mqtt_client()?);
@@ -0,0 +1,430 @@
+use super::properties::PublishProperties;
+use super::queue::{OfflineQueue, QueuedMessage};
+use super::reconnect::ReconnectOptions;
+use super::router::{validate_filter, TopicRouter};
+use super::{
+	build_message, connect_options, create_options, granted_qos, handle_message, session_present, status_message,
+	status_topic_from_env, Handler, MqttVersion,
+};
+use crate::message::Availability;
+use crate::utils::dotenv;
+use futures_util::stream::{Stream, StreamExt};
+use paho_mqtt as mqtt;
+use std::{
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+
+const STREAM_BUFFER_SIZE: usize = 1024;
+
+pub struct AsyncMqttClient {
+	client: mqtt::AsyncClient,
+	consumer: mqtt::AsyncReceiver<Option<mqtt::Message>>,
+	broker: String,
+	client_id: String,
+	version: MqttVersion,
+	session_expiry: Option<u32>,
+	status_topic: Option<String>,
+	reconnect: ReconnectOptions,
+	state: Mutex<ConnectionState>,
+	queue: Mutex<OfflineQueue>,
+	handlers: Mutex<TopicRouter<Handler>>,
+	sending: tokio::sync::Mutex<()>,
+	reconnecting: tokio::sync::Mutex<()>,
+}
+
+pub struct ClientOptions {
+	pub version: MqttVersion,
+	pub session_expiry: Option<u32>,
+	pub status_topic: Option<String>,
+	pub reconnect: ReconnectOptions,
+	pub queue: OfflineQueue,
+}
+
+impl ClientOptions {
+	pub fn from_env(client_id: &str) -> Self {
+		ClientOptions {
+			version: MqttVersion::from_env(),
+			session_expiry: dotenv::parse_var("MQTT_SESSION_EXPIRY"),
+			status_topic: status_topic_from_env(client_id),
+			reconnect: ReconnectOptions::from_env(),
+			queue: OfflineQueue::from_env(client_id),
+		}
+	}
+}
+
+#[derive(Default)]
+struct ConnectionState {
+	subscriptions: Vec<(String, i32)>,
+	attempts: u32,
+	retry_at: Option<Instant>,
+}
+
+impl AsyncMqttClient {
+	pub fn new(client_id: &str) -> Result<Self, mqtt::Error> {
+		Self::with_options(client_id, ClientOptions::from_env(client_id))
+	}
+
+	pub fn with_options(client_id: &str, options: ClientOptions) -> Result<Self, mqtt::Error> {
+		let (broker, create_opts) = create_options(client_id, options.version)?;
+
+		let mut client = mqtt::AsyncClient::new(create_opts)?;
+		let consumer = client.get_stream(STREAM_BUFFER_SIZE);
+		Ok(AsyncMqttClient {
+			client,
+			consumer,
+			broker,
+			client_id: client_id.to_owned(),
+			version: options.version,
+			session_expiry: options.session_expiry,
+			status_topic: options.status_topic.map(|topic| topic.replace("{id}", client_id)),
+			reconnect: options.reconnect,
+			state: Mutex::new(ConnectionState::default()),
+			queue: Mutex::new(options.queue),
+			handlers: Mutex::new(TopicRouter::new()),
+			sending: tokio::sync::Mutex::new(()),
+			reconnecting: tokio::sync::Mutex::new(()),
+		})
+	}
+
+	pub fn version(&self) -> MqttVersion {
+		self.version
+	}
+
+	// Returns whether the broker resumed a previous session, in which case the subscriptions are still in place
+	pub async fn connect(&self, keep_alive_interval: Duration, clean_session: bool) -> Result<bool, mqtt::Error> {
+		let will = self.status(Availability::Offline);
+		let options = connect_options(&self.broker, keep_alive_interval, clean_session, self.version, self.session_expiry, will)?;
+		let session_present = session_present(&self.client.connect(options).await?);
+		if session_present {
+			println!("Resumed the session on the broker at {}", self.broker);
+		} else {
+			println!("Connected to the broker at {}", self.broker);
+		}
+
+		self.publish_status(Availability::Online).await?;
+		Ok(session_present)
+	}
+
+	pub async fn publish(&self, topic: &str, payload: &str, qos: i32) -> Result<(), mqtt::Error> {
+		self.publish_with(topic, payload, qos, &PublishProperties::default()).await
+	}
+
+	// While the broker is unreachable messages are queued, and sent in order once a reconnection succeeds
+	pub async fn publish_with(&self, topic: &str, payload: &str, qos: i32, properties: &PublishProperties) -> Result<(), mqtt::Error> {
+		let message =
+			QueuedMessage { topic: topic.to_owned(), payload: payload.to_owned(), qos, properties: properties.clone() };
+
+		let _sending = self.sending.lock().await;
+		if self.ensure_connected().await && self.flush().await {
+			match self.send(&message).await {
+				Ok(()) => return Ok(()),
+				Err(e) if self.client.is_connected() => return Err(e),
+				Err(e) => eprintln!("Lost the connection to the broker at {}: {}", self.broker, e),
+			}
+		}
+
+		self.enqueue(message);
+		Ok(())
+	}
+
+	// Returns the QoS granted by the broker, which may be lower than the requested one
+	pub async fn subscribe(&self, topic: &str, qos: i32) -> Result<i32, mqtt::Error> {
+		let granted = granted_qos(&self.client.subscribe(topic, qos).await?, qos)?;
+
+		let mut state = self.state.lock().unwrap();
+		state.subscriptions.retain(|(subscribed, _)| subscribed != topic);
+		state.subscriptions.push((topic.to_owned(), qos));
+		Ok(granted)
+	}
+
+	pub async fn unsubscribe(&self, topic: &str) -> Result<(), mqtt::Error> {
+		self.client.unsubscribe(topic).await?;
+
+		self.state.lock().unwrap().subscriptions.retain(|(subscribed, _)| subscribed != topic);
+		Ok(())
+	}
+
+	// Handlers run while the routes are locked, so they can not add or remove routes themselves
+	pub async fn route<F>(&self, filter: &str, qos: i32, handler: F) -> Result<(), mqtt::Error>
+	where
+		F: FnMut(&mqtt::Message) + Send + 'static,
+	{
+		validate_filter(filter).map_err(mqtt::Error::General)?;
+		self.subscribe(filter, qos).await?;
+		self.handlers.lock().unwrap().add(filter, Box::new(handler)).map_err(mqtt::Error::General)
+	}
+
+	pub async fn unroute(&self, filter: &str) -> Result<(), mqtt::Error> {
+		self.handlers.lock().unwrap().remove(filter);
+		self.unsubscribe(filter).await
+	}
+
+	pub fn handle(&self, message: &mqtt::Message) -> bool {
+		handle_message(&self.handlers, message)
+	}
+
+	pub async fn dispatch(&self) {
+		while let Some(message) = self.next_message().await {
+			if !self.handle(&message) {
+				eprintln!("No handler for the message received on {}", message.topic());
+			}
+		}
+	}
+
+	pub async fn ensure_connected(&self) -> bool {
+		if self.client.is_connected() {
+			return true;
+		}
+
+		// Concurrent callers wait for a single reconnection attempt instead of starting their own
+		let _reconnecting = self.reconnecting.lock().await;
+		if self.client.is_connected() {
+			return true;
+		}
+
+		if self.state.lock().unwrap().retry_at.is_some_and(|retry_at| Instant::now() < retry_at) {
+			return false;
+		}
+
+		if let Err(e) = self.client.reconnect().await {
+			let mut state = self.state.lock().unwrap();
+			let delay = self.reconnect.delay(state.attempts, &mut rand::thread_rng());
+			state.attempts = state.attempts.saturating_add(1);
+			state.retry_at = Some(Instant::now() + delay);
+
+			eprintln!("Failed to reconnect to the broker at {}, retrying in {:.1}s: {}", self.broker, delay.as_secs_f64(), e);
+			return false;
+		}
+
+		let subscriptions = {
+			let mut state = self.state.lock().unwrap();
+			state.attempts = 0;
+			state.retry_at = None;
+			state.subscriptions.clone()
+		};
+		println!("Reconnected to the broker at {}", self.broker);
+
+		if let Err(e) = self.publish_status(Availability::Online).await {
+			eprintln!("Failed to publish the online status of {}: {}", self.client_id, e);
+		}
+
+		for (topic, qos) in subscriptions {
+			if let Err(e) = self.client.subscribe(&topic, qos).await {
+				eprintln!("Failed to resubscribe to {}: {}", topic, e);
+			}
+		}
+
+		true
+	}
+
+	pub fn queued_messages(&self) -> usize {
+		self.queue.lock().unwrap().len()
+	}
+
+	pub fn dropped_messages(&self) -> u64 {
+		self.queue.lock().unwrap().dropped()
+	}
+
+	// Fails when messages are left in the offline queue or were dropped from it, a persistent queue keeps the unsent
+	// ones for the next start
+	pub async fn disconnect(&self) -> Result<(), mqtt::Error> {
+		let _sending = self.sending.lock().await;
+		if self.ensure_connected().await && self.flush().await {
+			self.publish_status(Availability::Offline).await?;
+		}
+		if self.client.is_connected() {
+			self.client.disconnect(None).await?;
+		}
+
+		let (queued, dropped) = {
+			let queue = self.queue.lock().unwrap();
+			(queue.len(), queue.dropped())
+		};
+		if queued > 0 || dropped > 0 {
+			return Err(mqtt::Error::GeneralString(format!(
+				"{} messages were left unsent and {} were dropped while offline",
+				queued, dropped
+			)));
+		}
+		Ok(())
+	}
+
+	async fn send(&self, message: &QueuedMessage) -> Result<(), mqtt::Error> {
+		let msg = build_message(&message.topic, &message.payload, message.qos, &message.properties, self.version)?;
+
+		self.client.publish(msg).await
+	}
+
+	fn status(&self, status: Availability) -> Option<mqtt::Message> {
+		self.status_topic.as_deref().map(|topic| status_message(topic, &self.client_id, status))
+	}
+
+	async fn publish_status(&self, status: Availability) -> Result<(), mqtt::Error> {
+		match self.status(status) {
+			Some(message) => self.client.publish(message).await,
+			None => Ok(()),
+		}
+	}
+
+	fn enqueue(&self, message: QueuedMessage) {
+		if let Err(e) = self.queue.lock().unwrap().push(message) {
+			eprintln!("Failed to write the offline queue: {}", e);
+		}
+	}
+
+	// Callers hold the sending lock, so a queued message is sent and removed before anyone else sends
+	async fn flush(&self) -> bool {
+		let (queued, dropped) = {
+			let queue = self.queue.lock().unwrap();
+			(queue.len(), queue.dropped())
+		};
+		if queued == 0 {
+			return true;
+		}
+		println!("Sending {} queued messages to the broker at {}, {} dropped so far", queued, self.broker, dropped);
+
+		let mut connected = true;
+		loop {
+			let Some(message) = self.queue.lock().unwrap().front().cloned() else {
+				break;
+			};
+
+			if let Err(e) = self.send(&message).await {
+				if !self.client.is_connected() {
+					eprintln!("Lost the connection to the broker at {}: {}", self.broker, e);
+					connected = false;
+					break;
+				}
+				eprintln!("Failed to send a queued message to {}, discarding it: {}", message.topic, e);
+			}
+			self.queue.lock().unwrap().pop_front();
+		}
+
+		if let Err(e) = self.queue.lock().unwrap().save() {
+			eprintln!("Failed to write the offline queue: {}", e);
+		}
+		connected
+	}
+
+	pub fn messages(&self) -> impl Stream<Item = mqtt::Message> {
+		self.consumer.clone().filter_map(|message| async move { message })
+	}
+
+	pub async fn next_message(&self) -> Option<mqtt::Message> {
+		loop {
+			match self.consumer.recv().await {
+				Ok(Some(message)) => return Some(message),
+				// None is sent when the connection is lost, keep waiting for the reconnection
+				Ok(None) => continue,
+				Err(_) => return None,
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::error::Error;
+
+	#[tokio::test]
+	async fn test_server_responses() -> Result<(), Box<dyn Error>> {
+		let client_id = format!("test_async_mqtt_client-{}", rand::random::<u16>());
+
+		let client = AsyncMqttClient::new(&client_id)?;
+		assert!(!client.connect(Duration::from_secs(60), false).await?, "A new session should not be resumed");
+		assert_eq!(client.subscribe("test_async_server_responses", 1).await?, 1);
+		client.disconnect().await?;
+
+		let client = AsyncMqttClient::new(&client_id)?;
+		assert!(!client.connect(Duration::from_secs(60), true).await?, "A clean session should not be resumed");
+		client.disconnect().await?;
+		Ok(())
+	}
+
+	async fn setup_mqtt_client() -> Result<AsyncMqttClient, Box<dyn Error>> {
+		let client = AsyncMqttClient::new(format!("test_async_mqtt_client-{}", rand::random::<u16>()).as_str())?;
+		client.connect(Duration::from_secs(60), true).await?;
+		Ok(client)
+	}
+
+	#[tokio::test]
+	async fn test_message_stream() -> Result<(), Box<dyn Error>> {
+		let client = setup_mqtt_client().await?;
+		let topic = "test_async_message_stream";
+		let mut messages = Box::pin(client.messages());
+		client.subscribe(topic, 1).await?;
+
+		for i in 0..10 {
+			client.publish(topic, &format!("Test message {}", i), 1).await?;
+		}
+
+		for i in 0..10 {
+			let message = tokio::time::timeout(Duration::from_secs(5), messages.next())
+				.await?
+				.expect("Message stream should not end");
+			assert_eq!(message.topic(), topic);
+			assert_eq!(message.payload_str(), format!("Test message {}", i));
+		}
+
+		client.unsubscribe(topic).await?;
+		client.disconnect().await?;
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_concurrent_clients() -> Result<(), Box<dyn Error>> {
+		let handles: Vec<_> = (0..50)
+			.map(|_| {
+				tokio::spawn(async {
+					let client = setup_mqtt_client().await.expect("Failed to connect");
+					client.publish("test_async_concurrent_clients", "Test message", 0).await.expect("Failed to publish message");
+					client.disconnect().await.expect("Failed to disconnect");
+				})
+			})
+			.collect();
+
+		for handle in handles {
+			handle.await?;
+		}
+
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn test_concurrent_publishes() -> Result<(), Box<dyn Error>> {
+		let client = std::sync::Arc::new(setup_mqtt_client().await?);
+		let topic = "test_async_concurrent_publishes";
+		let mut messages = Box::pin(client.messages());
+		client.subscribe(topic, 1).await?;
+
+		let handles: Vec<_> = (0..20)
+			.map(|i| {
+				let client = client.clone();
+				tokio::spawn(async move {
+					client.publish(topic, &format!("Test message {}", i), 1).await.expect("Failed to publish message");
+				})
+			})
+			.collect();
+		for handle in handles {
+			handle.await?;
+		}
+
+		let mut received = Vec::new();
+		for _ in 0..20 {
+			let message = tokio::time::timeout(Duration::from_secs(5), messages.next())
+				.await?
+				.expect("Message stream should not end");
+			received.push(message.payload_str().into_owned());
+		}
+		received.sort();
+		received.dedup();
+		assert_eq!(received.len(), 20, "Every message should be delivered once");
+		assert_eq!(client.queued_messages(), 0);
+
+		client.unsubscribe(topic).await?;
+		client.disconnect().await?;
+		Ok(())
+	}
+}
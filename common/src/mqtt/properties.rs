@@ -0,0 +1,88 @@
+use paho_mqtt as mqtt;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PublishProperties {
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub user_properties: Vec<(String, String)>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub content_type: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub message_expiry: Option<u32>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub topic_alias: Option<u16>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub response_topic: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub correlation_data: Option<Vec<u8>>,
+}
+
+impl PublishProperties {
+	pub fn is_empty(&self) -> bool {
+		*self == Self::default()
+	}
+
+	pub fn user_property(&self, key: &str) -> Option<&str> {
+		self.user_properties.iter().find(|(name, _)| name == key).map(|(_, value)| value.as_str())
+	}
+
+	pub fn to_properties(&self) -> Result<mqtt::Properties, mqtt::Error> {
+		let mut properties = mqtt::Properties::new();
+
+		for (key, value) in &self.user_properties {
+			properties.push_string_pair(mqtt::PropertyCode::UserProperty, key, value)?;
+		}
+		if let Some(content_type) = &self.content_type {
+			properties.push_string(mqtt::PropertyCode::ContentType, content_type)?;
+		}
+		if let Some(expiry) = self.message_expiry {
+			properties.push_u32(mqtt::PropertyCode::MessageExpiryInterval, expiry)?;
+		}
+		if let Some(alias) = self.topic_alias {
+			properties.push_u16(mqtt::PropertyCode::TopicAlias, alias)?;
+		}
+		if let Some(topic) = &self.response_topic {
+			properties.push_string(mqtt::PropertyCode::ResponseTopic, topic)?;
+		}
+		if let Some(data) = &self.correlation_data {
+			properties.push_binary(mqtt::PropertyCode::CorrelationData, data.clone())?;
+		}
+
+		Ok(properties)
+	}
+}
+
+impl From<&mqtt::Properties> for PublishProperties {
+	fn from(properties: &mqtt::Properties) -> Self {
+		PublishProperties {
+			user_properties: properties.user_iter().collect(),
+			content_type: properties.get_string(mqtt::PropertyCode::ContentType),
+			message_expiry: properties.get_int(mqtt::PropertyCode::MessageExpiryInterval).map(|expiry| expiry as u32),
+			topic_alias: properties.get_int(mqtt::PropertyCode::TopicAlias).map(|alias| alias as u16),
+			response_topic: properties.get_string(mqtt::PropertyCode::ResponseTopic),
+			correlation_data: properties.get_binary(mqtt::PropertyCode::CorrelationData),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_properties_round_trip() {
+		let properties = PublishProperties {
+			user_properties: vec![("firmware".to_owned(), "2.1.0".to_owned()), ("site".to_owned(), "lab".to_owned())],
+			content_type: Some("application/json".to_owned()),
+			message_expiry: Some(60),
+			topic_alias: Some(3),
+			response_topic: Some("devices/station-1/responses".to_owned()),
+			correlation_data: Some(b"request-1".to_vec()),
+		};
+
+		let converted = PublishProperties::from(&properties.to_properties().expect("Failed to convert properties"));
+		assert_eq!(converted, properties);
+		assert_eq!(converted.user_property("firmware"), Some("2.1.0"));
+		assert!(PublishProperties::from(&mqtt::Properties::new()).is_empty());
+	}
+}
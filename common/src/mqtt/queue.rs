@@ -0,0 +1,171 @@
+use super::properties::PublishProperties;
+use crate::utils::dotenv;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::VecDeque,
+	fs::{self, File, OpenOptions},
+	io::{self, BufRead, BufReader, BufWriter, Write},
+	path::{Path, PathBuf},
+};
+
+const DEFAULT_CAPACITY: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedMessage {
+	pub topic: String,
+	pub payload: String,
+	pub qos: i32,
+	#[serde(default, skip_serializing_if = "PublishProperties::is_empty")]
+	pub properties: PublishProperties,
+}
+
+pub struct OfflineQueue {
+	messages: VecDeque<QueuedMessage>,
+	capacity: usize,
+	file: Option<PathBuf>,
+	dropped: u64,
+}
+
+impl OfflineQueue {
+	pub fn new(capacity: usize) -> Self {
+		OfflineQueue { messages: VecDeque::new(), capacity, file: None, dropped: 0 }
+	}
+
+	pub fn persistent(capacity: usize, file: &Path) -> io::Result<Self> {
+		let mut queue = OfflineQueue { file: Some(file.to_owned()), ..Self::new(capacity) };
+
+		if file.exists() {
+			for (index, line) in BufReader::new(File::open(file)?).lines().enumerate() {
+				match serde_json::from_str(&line?) {
+					Ok(message) => queue.messages.push_back(message),
+					Err(e) => eprintln!("Skipping invalid message {} of {}: {}", index + 1, file.display(), e),
+				}
+			}
+			queue.truncate();
+			queue.save()?;
+		} else if let Some(parent) = file.parent() {
+			fs::create_dir_all(parent)?;
+		}
+
+		Ok(queue)
+	}
+
+	pub fn from_env(client_id: &str) -> Self {
+		let capacity = dotenv::parse_var("MQTT_QUEUE_SIZE").unwrap_or(DEFAULT_CAPACITY);
+
+		let Some(dir) = dotenv::get_var("MQTT_QUEUE_DIR").ok().filter(|dir| !dir.is_empty()) else {
+			return Self::new(capacity);
+		};
+		let file = Path::new(&dir).join(format!("{}.jsonl", client_id));
+
+		Self::persistent(capacity, &file).unwrap_or_else(|e| {
+			eprintln!("Failed to open the offline queue {}, keeping it in memory: {}", file.display(), e);
+			Self::new(capacity)
+		})
+	}
+
+	pub fn push(&mut self, message: QueuedMessage) -> io::Result<()> {
+		self.messages.push_back(message);
+
+		if self.truncate() {
+			self.save()
+		} else {
+			self.append()
+		}
+	}
+
+	pub fn front(&self) -> Option<&QueuedMessage> {
+		self.messages.front()
+	}
+
+	pub fn pop_front(&mut self) -> Option<QueuedMessage> {
+		self.messages.pop_front()
+	}
+
+	pub fn len(&self) -> usize {
+		self.messages.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.messages.is_empty()
+	}
+
+	pub fn dropped(&self) -> u64 {
+		self.dropped
+	}
+
+	pub fn save(&self) -> io::Result<()> {
+		let Some(file) = &self.file else {
+			return Ok(());
+		};
+
+		let mut writer = BufWriter::new(File::create(file)?);
+		for message in &self.messages {
+			writeln!(writer, "{}", serde_json::to_string(message)?)?;
+		}
+		writer.flush()
+	}
+
+	fn append(&self) -> io::Result<()> {
+		let (Some(file), Some(message)) = (&self.file, self.messages.back()) else {
+			return Ok(());
+		};
+
+		let mut file = OpenOptions::new().create(true).append(true).open(file)?;
+		writeln!(file, "{}", serde_json::to_string(message)?)
+	}
+
+	fn truncate(&mut self) -> bool {
+		let overflow = self.messages.len().saturating_sub(self.capacity);
+		self.messages.drain(..overflow);
+		self.dropped += overflow as u64;
+
+		overflow > 0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn message(index: usize) -> QueuedMessage {
+		QueuedMessage {
+			topic: "sensors".to_owned(),
+			payload: format!("Test message {}", index),
+			qos: 1,
+			properties: PublishProperties::default(),
+		}
+	}
+
+	#[test]
+	fn test_queue_drops_oldest() {
+		let mut queue = OfflineQueue::new(3);
+		for index in 0..5 {
+			queue.push(message(index)).expect("Failed to queue message");
+		}
+
+		assert_eq!(queue.len(), 3);
+		assert_eq!(queue.dropped(), 2);
+		assert_eq!(queue.pop_front(), Some(message(2)));
+		assert_eq!(queue.front(), Some(&message(3)));
+	}
+
+	#[test]
+	fn test_persistent_queue() {
+		let file = std::env::temp_dir().join(format!("test_offline_queue-{}", rand::random::<u32>())).join("station-1.jsonl");
+
+		let mut queue = OfflineQueue::persistent(3, &file).expect("Failed to open queue");
+		for index in 0..4 {
+			queue.push(message(index)).expect("Failed to queue message");
+		}
+		queue.pop_front();
+		queue.save().expect("Failed to save queue");
+
+		let mut queue = OfflineQueue::persistent(3, &file).expect("Failed to reopen queue");
+		assert_eq!(queue.len(), 2);
+		assert_eq!(queue.pop_front(), Some(message(2)));
+		assert_eq!(queue.pop_front(), Some(message(3)));
+
+		fs::remove_dir_all(file.parent().unwrap()).expect("Failed to remove queue");
+	}
+}
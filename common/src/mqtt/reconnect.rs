@@ -0,0 +1,66 @@
+use crate::utils::dotenv;
+use rand::Rng;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectOptions {
+	pub min_delay: Duration,
+	pub max_delay: Duration,
+	pub jitter: f64,
+}
+
+impl Default for ReconnectOptions {
+	fn default() -> Self {
+		ReconnectOptions { min_delay: Duration::from_secs(1), max_delay: Duration::from_secs(60), jitter: 0.5 }
+	}
+}
+
+impl ReconnectOptions {
+	pub fn from_env() -> Self {
+		let default = Self::default();
+
+		ReconnectOptions {
+			min_delay: seconds_var("MQTT_RECONNECT_MIN_DELAY").unwrap_or(default.min_delay),
+			max_delay: seconds_var("MQTT_RECONNECT_MAX_DELAY").unwrap_or(default.max_delay),
+			jitter: dotenv::parse_var::<f64>("MQTT_RECONNECT_JITTER").map_or(default.jitter, |jitter| jitter.clamp(0.0, 1.0)),
+		}
+	}
+
+	// Exponential backoff, shortened by a random fraction of up to `jitter` so devices disconnected together spread their reconnections
+	pub fn delay(&self, attempt: u32, rng: &mut impl Rng) -> Duration {
+		let backoff = self.min_delay.saturating_mul(2u32.saturating_pow(attempt)).min(self.max_delay);
+
+		backoff.mul_f64(1.0 - self.jitter * rng.gen::<f64>())
+	}
+}
+
+fn seconds_var(key: &str) -> Option<Duration> {
+	dotenv::parse_var::<f64>(key).filter(|seconds| seconds.is_finite() && *seconds >= 0.0).map(Duration::from_secs_f64)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::{rngs::StdRng, SeedableRng};
+
+	#[test]
+	fn test_reconnect_backoff() {
+		let options = ReconnectOptions { min_delay: Duration::from_secs(1), max_delay: Duration::from_secs(30), jitter: 0.0 };
+		let mut rng = StdRng::seed_from_u64(7);
+		let delays: Vec<_> = (0..7).map(|attempt| options.delay(attempt, &mut rng).as_secs()).collect();
+
+		assert_eq!(delays, [1, 2, 4, 8, 16, 30, 30]);
+		assert_eq!(options.delay(u32::MAX, &mut rng), Duration::from_secs(30), "Backoff should not overflow");
+	}
+
+	#[test]
+	fn test_reconnect_jitter() {
+		let options = ReconnectOptions { min_delay: Duration::from_secs(4), max_delay: Duration::from_secs(60), jitter: 0.5 };
+		let mut rng = StdRng::seed_from_u64(7);
+
+		for _ in 0..100 {
+			let delay = options.delay(2, &mut rng);
+			assert!(delay >= Duration::from_secs(8) && delay <= Duration::from_secs(16), "Delay out of range: {:?}", delay);
+		}
+	}
+}
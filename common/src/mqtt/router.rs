@@ -0,0 +1,145 @@
+const SHARED_PREFIX: &str = "$share/";
+
+pub struct TopicRouter<H> {
+	routes: Vec<(String, H)>,
+}
+
+impl<H> Default for TopicRouter<H> {
+	fn default() -> Self {
+		TopicRouter { routes: Vec::new() }
+	}
+}
+
+impl<H> TopicRouter<H> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn add(&mut self, filter: &str, handler: H) -> Result<(), &'static str> {
+		validate_filter(filter)?;
+		self.routes.push((filter.to_owned(), handler));
+		Ok(())
+	}
+
+	pub fn remove(&mut self, filter: &str) -> bool {
+		let routes = self.routes.len();
+		self.routes.retain(|(route, _)| route != filter);
+		self.routes.len() < routes
+	}
+
+	pub fn matching<'a>(&'a mut self, topic: &'a str) -> impl Iterator<Item = &'a mut H> {
+		self.routes.iter_mut().filter(|(filter, _)| topic_matches(filter, topic)).map(|(_, handler)| handler)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.routes.is_empty()
+	}
+}
+
+pub fn validate_filter(filter: &str) -> Result<(), &'static str> {
+	let filter = match filter.strip_prefix(SHARED_PREFIX) {
+		Some(shared) => {
+			let (group, filter) = shared.split_once('/').ok_or("shared subscriptions need a group and a topic filter")?;
+			if group.is_empty() || group.contains(['+', '#']) {
+				return Err("shared subscription group must not be empty or contain wildcards");
+			}
+			filter
+		}
+		None => filter,
+	};
+
+	if filter.is_empty() {
+		return Err("topic filter must not be empty");
+	}
+
+	let levels: Vec<_> = filter.split('/').collect();
+	for (index, level) in levels.iter().enumerate() {
+		if level.contains('#') && (*level != "#" || index != levels.len() - 1) {
+			return Err("# must be the whole last level of a topic filter");
+		}
+		if level.contains('+') && *level != "+" {
+			return Err("+ must be a whole level of a topic filter");
+		}
+	}
+
+	Ok(())
+}
+
+pub fn topic_matches(filter: &str, topic: &str) -> bool {
+	let filter = match filter.strip_prefix(SHARED_PREFIX) {
+		Some(shared) => shared.split_once('/').map_or("", |(_, filter)| filter),
+		None => filter,
+	};
+
+	// Wildcards at the first level do not match system topics, like $SYS/broker/uptime
+	if topic.starts_with('$') && (filter.starts_with('+') || filter.starts_with('#')) {
+		return false;
+	}
+
+	let mut topic_levels = topic.split('/');
+	for level in filter.split('/') {
+		match (level, topic_levels.next()) {
+			("#", _) => return true,
+			("+", Some(_)) => {}
+			(level, Some(topic_level)) if level == topic_level => {}
+			_ => return false,
+		}
+	}
+
+	topic_levels.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_topic_matches() {
+		let cases = [
+			("sensors", "sensors", true),
+			("sensors", "sensors/station-1", false),
+			("devices/+/commands", "devices/station-1/commands", true),
+			("devices/+/commands", "devices/station-1/status", false),
+			("devices/+/commands", "devices//commands", true),
+			("devices/#", "devices", true),
+			("devices/#", "devices/station-1/commands/interval", true),
+			("devices/+", "devices/station-1/commands", false),
+			("+/+", "/sensors", true),
+			("#", "devices/station-1", true),
+			("#", "$SYS/broker/uptime", false),
+			("$SYS/#", "$SYS/broker/uptime", true),
+			("$share/simulators/devices/+/commands", "devices/station-1/commands", true),
+			("$share/simulators/devices/+/commands", "devices/station-1/status", false),
+		];
+
+		for (filter, topic, matches) in cases {
+			assert_eq!(topic_matches(filter, topic), matches, "{} should match {}: {}", filter, topic, matches);
+		}
+	}
+
+	#[test]
+	fn test_invalid_filters() {
+		for filter in ["", "devices/#/commands", "devices/station#", "devices/station+", "$share/", "$share//sensors", "$share/a+/sensors"] {
+			assert!(validate_filter(filter).is_err(), "{} should be invalid", filter);
+		}
+		for filter in ["devices/+/commands", "#", "+", "$share/simulators/devices/#"] {
+			assert!(validate_filter(filter).is_ok(), "{} should be valid", filter);
+		}
+	}
+
+	#[test]
+	fn test_router_dispatch() {
+		let mut router = TopicRouter::new();
+		router.add("devices/+/commands", "commands").unwrap();
+		router.add("devices/#", "devices").unwrap();
+		router.add("$share/group/sensors", "sensors").unwrap();
+		assert!(router.add("devices/#/status", "status").is_err());
+
+		assert_eq!(router.matching("devices/station-1/commands").map(|handler| *handler).collect::<Vec<_>>(), ["commands", "devices"]);
+		assert_eq!(router.matching("sensors").map(|handler| *handler).collect::<Vec<_>>(), ["sensors"]);
+		assert_eq!(router.matching("status").count(), 0);
+
+		assert!(router.remove("devices/#"));
+		assert_eq!(router.matching("devices/station-1/status").count(), 0);
+	}
+}
@@ -0,0 +1,130 @@
+use crate::utils::dotenv;
+use paho_mqtt as mqtt;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transport {
+	Tcp,
+	Ssl,
+	Ws,
+	Wss,
+}
+
+impl Transport {
+	pub fn from_uri(uri: &str) -> Option<Self> {
+		let scheme = uri.split_once("://").map_or("tcp", |(scheme, _)| scheme);
+
+		match scheme.to_ascii_lowercase().as_str() {
+			"tcp" | "mqtt" => Some(Transport::Tcp),
+			"ssl" | "mqtts" => Some(Transport::Ssl),
+			"ws" => Some(Transport::Ws),
+			"wss" => Some(Transport::Wss),
+			_ => None,
+		}
+	}
+
+	pub fn is_secure(self) -> bool {
+		matches!(self, Transport::Ssl | Transport::Wss)
+	}
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TlsOptions {
+	pub ca_file: Option<PathBuf>,
+	pub cert_file: Option<PathBuf>,
+	pub key_file: Option<PathBuf>,
+	pub key_password: Option<String>,
+	pub alpn: Vec<String>,
+	pub insecure: bool,
+}
+
+impl TlsOptions {
+	pub fn from_env() -> Self {
+		TlsOptions {
+			ca_file: dotenv::parse_var("BROKER_CA_FILE"),
+			cert_file: dotenv::parse_var("BROKER_CERT_FILE"),
+			key_file: dotenv::parse_var("BROKER_KEY_FILE"),
+			key_password: dotenv::parse_var("BROKER_KEY_PASSWORD"),
+			alpn: dotenv::parse_var::<String>("BROKER_ALPN")
+				.map(|protocols| protocols.split(',').map(str::trim).filter(|protocol| !protocol.is_empty()).map(String::from).collect())
+				.unwrap_or_default(),
+			insecure: dotenv::parse_var("BROKER_TLS_INSECURE").unwrap_or(false),
+		}
+	}
+
+	pub fn validate(&self) -> Result<(), &'static str> {
+		if self.key_file.is_some() && self.cert_file.is_none() {
+			return Err("BROKER_KEY_FILE requires a BROKER_CERT_FILE");
+		}
+		if self.key_password.is_some() && self.key_file.is_none() {
+			return Err("BROKER_KEY_PASSWORD requires a BROKER_KEY_FILE");
+		}
+
+		Ok(())
+	}
+
+	pub fn ssl_options(&self) -> Result<mqtt::SslOptions, mqtt::Error> {
+		self.validate().map_err(mqtt::Error::General)?;
+
+		let mut builder = mqtt::SslOptionsBuilder::new();
+		if let Some(ca_file) = &self.ca_file {
+			builder.trust_store(ca_file)?;
+		}
+		if let Some(cert_file) = &self.cert_file {
+			builder.key_store(cert_file)?;
+		}
+		if let Some(key_file) = &self.key_file {
+			builder.private_key(key_file)?;
+		}
+		if let Some(password) = &self.key_password {
+			builder.private_key_password(password);
+		}
+		if !self.alpn.is_empty() {
+			builder.alpn_protos(&self.alpn.iter().map(String::as_str).collect::<Vec<_>>());
+		}
+
+		if self.insecure {
+			eprintln!("TLS certificate verification of the broker is disabled");
+		}
+		builder.enable_server_cert_auth(!self.insecure).verify(!self.insecure);
+
+		Ok(builder.finalize())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_transport_from_uri() {
+		let cases = [
+			("tcp://localhost:1883", Some(Transport::Tcp)),
+			("localhost:1883", Some(Transport::Tcp)),
+			("mqtt://localhost:1883", Some(Transport::Tcp)),
+			("ssl://broker.hivemq.cloud:8883", Some(Transport::Ssl)),
+			("MQTTS://broker.hivemq.cloud:8883", Some(Transport::Ssl)),
+			("ws://localhost:8080/mqtt", Some(Transport::Ws)),
+			("wss://broker.hivemq.cloud:8884/mqtt", Some(Transport::Wss)),
+			("http://localhost:1883", None),
+		];
+
+		for (uri, transport) in cases {
+			assert_eq!(Transport::from_uri(uri), transport, "Wrong transport for {}", uri);
+		}
+		assert!(Transport::Wss.is_secure());
+		assert!(!Transport::Ws.is_secure());
+	}
+
+	#[test]
+	fn test_tls_options() {
+		let options = TlsOptions { key_file: Some("client.key".into()), ..TlsOptions::default() };
+		assert_eq!(options.validate(), Err("BROKER_KEY_FILE requires a BROKER_CERT_FILE"));
+
+		let options = TlsOptions { insecure: true, alpn: vec!["mqtt".to_owned()], ..TlsOptions::default() };
+		assert!(options.ssl_options().is_ok());
+
+		let options = TlsOptions { ca_file: Some("missing-ca.pem".into()), ..TlsOptions::default() };
+		assert!(options.ssl_options().is_err(), "A missing CA file should be rejected");
+	}
+}
@@ -17,4 +17,14 @@ pub mod dotenv {
 			Err(e) => Err(e),
 		}
 	}
+
+	pub fn parse_var<T: std::str::FromStr>(key: &str) -> Option<T> {
+		let value = get_var(key).ok().filter(|value| !value.trim().is_empty())?;
+
+		let parsed = value.trim().parse().ok();
+		if parsed.is_none() {
+			eprintln!("Invalid {} value {:?}, using the default", key, value);
+		}
+		parsed
+	}
 }
@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use std::time::{Duration, Instant};
+
+pub const MIN_SPEED: f64 = 1e-6;
+pub const MAX_SPEED: f64 = 1e6;
+
+pub trait Clock: Send {
+	fn now(&self) -> DateTime<Utc>;
+	fn delay_until(&mut self, at: DateTime<Utc>) -> Duration;
+}
+
+pub enum ClockMode {
+	Real,
+	Accelerated { speed: f64, start: Option<DateTime<Utc>> },
+	Virtual { start: Option<DateTime<Utc>> },
+}
+
+impl ClockMode {
+	pub fn start(&self) -> Option<DateTime<Utc>> {
+		match self {
+			ClockMode::Real => None,
+			ClockMode::Accelerated { start, .. } | ClockMode::Virtual { start } => *start,
+		}
+	}
+
+	pub fn build(&self, origin: Instant, start: DateTime<Utc>) -> Box<dyn Clock> {
+		match self {
+			ClockMode::Real => Box::new(RealClock),
+			ClockMode::Accelerated { speed, .. } => Box::new(AcceleratedClock::new(origin, start, *speed)),
+			ClockMode::Virtual { .. } => Box::new(VirtualClock::new(start)),
+		}
+	}
+}
+
+pub struct RealClock;
+
+impl Clock for RealClock {
+	fn now(&self) -> DateTime<Utc> {
+		Utc::now()
+	}
+
+	fn delay_until(&mut self, at: DateTime<Utc>) -> Duration {
+		(at - self.now()).to_std().unwrap_or(Duration::ZERO)
+	}
+}
+
+pub struct AcceleratedClock {
+	origin: Instant,
+	start: DateTime<Utc>,
+	speed: f64,
+}
+
+impl AcceleratedClock {
+	pub fn new(origin: Instant, start: DateTime<Utc>, speed: f64) -> Self {
+		AcceleratedClock { origin, start, speed }
+	}
+}
+
+impl Clock for AcceleratedClock {
+	fn now(&self) -> DateTime<Utc> {
+		let elapsed = self.origin.elapsed().mul_f64(self.speed);
+		self.start + chrono::Duration::from_std(elapsed).unwrap_or(chrono::Duration::MAX)
+	}
+
+	fn delay_until(&mut self, at: DateTime<Utc>) -> Duration {
+		let delay = (at - self.now()).to_std().unwrap_or(Duration::ZERO);
+		Duration::try_from_secs_f64(delay.as_secs_f64() / self.speed).unwrap_or(Duration::MAX)
+	}
+}
+
+pub struct VirtualClock {
+	now: DateTime<Utc>,
+}
+
+impl VirtualClock {
+	pub fn new(start: DateTime<Utc>) -> Self {
+		VirtualClock { now: start }
+	}
+}
+
+impl Clock for VirtualClock {
+	fn now(&self) -> DateTime<Utc> {
+		self.now
+	}
+
+	fn delay_until(&mut self, at: DateTime<Utc>) -> Duration {
+		self.now = self.now.max(at);
+		Duration::ZERO
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_real_clock() {
+		let mut clock = RealClock;
+		let at = clock.now() + chrono::Duration::milliseconds(500);
+
+		let delay = clock.delay_until(at);
+		assert!(delay > Duration::from_millis(400) && delay <= Duration::from_millis(500), "Unexpected delay {:?}", delay);
+		assert_eq!(clock.delay_until(at - chrono::Duration::seconds(1)), Duration::ZERO);
+	}
+
+	#[test]
+	fn test_accelerated_clock() {
+		let start = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+		let mut clock = AcceleratedClock::new(Instant::now(), start, 3600.0);
+
+		let delay = clock.delay_until(start + chrono::Duration::hours(1));
+		assert!(delay > Duration::from_millis(900) && delay <= Duration::from_secs(1), "Unexpected delay {:?}", delay);
+		assert!(clock.now() >= start);
+	}
+
+	#[test]
+	fn test_speed_bounds() {
+		let start = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+
+		let mut slow = AcceleratedClock::new(Instant::now(), start, MIN_SPEED);
+		assert!(slow.delay_until(start + chrono::Duration::days(1)) > Duration::from_secs(86_000_000_000));
+		assert!(slow.delay_until(DateTime::<Utc>::MAX_UTC) > Duration::from_secs(8_000_000_000_000_000_000));
+
+		let mut fast = AcceleratedClock::new(Instant::now(), start, MAX_SPEED);
+		assert!(fast.now() >= start);
+		assert!(fast.delay_until(start + chrono::Duration::days(1)) <= Duration::from_millis(87));
+	}
+
+	#[test]
+	fn test_virtual_clock() {
+		let start = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+		let mut clock = VirtualClock::new(start);
+
+		assert_eq!(clock.delay_until(start + chrono::Duration::days(1)), Duration::ZERO);
+		assert_eq!(clock.now(), start + chrono::Duration::days(1));
+
+		clock.delay_until(start);
+		assert_eq!(clock.now(), start + chrono::Duration::days(1), "Virtual time should never go backwards");
+	}
+}
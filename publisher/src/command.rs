@@ -0,0 +1,186 @@
+use crate::fault::FaultConfig;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const MAX_INTERVAL: f64 = 24.0 * 3600.0;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Command {
+	SetInterval { sensor: Option<String>, interval: f64 },
+	Reset,
+	Calibrate { sensor: Option<String>, offset: f32 },
+	InjectFault { sensor: Option<String>, fault: FaultConfig },
+	ClearFaults { sensor: Option<String> },
+	Shutdown,
+}
+
+pub struct CommandMessage {
+	pub id: Option<String>,
+	pub command: Result<Command, String>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CommandStatus {
+	Ok,
+	Error,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommandResponse {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub id: Option<String>,
+	pub device_id: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub command: Option<String>,
+	pub status: CommandStatus,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Request {
+	id: Option<String>,
+	#[serde(flatten)]
+	command: Command,
+}
+
+impl Command {
+	pub fn name(&self) -> &'static str {
+		match self {
+			Command::SetInterval { .. } => "set_interval",
+			Command::Reset => "reset",
+			Command::Calibrate { .. } => "calibrate",
+			Command::InjectFault { .. } => "inject_fault",
+			Command::ClearFaults { .. } => "clear_faults",
+			Command::Shutdown => "shutdown",
+		}
+	}
+
+	pub fn validate(&self) -> Result<(), String> {
+		match self {
+			Command::SetInterval { interval, .. } if !interval.is_finite() || *interval <= 0.0 => {
+				Err("interval must be a positive number".to_owned())
+			}
+			Command::SetInterval { interval, .. } if *interval > MAX_INTERVAL => {
+				Err(format!("interval must be at most {} seconds", MAX_INTERVAL))
+			}
+			Command::Calibrate { offset, .. } if !offset.is_finite() => Err("offset must be a finite number".to_owned()),
+			Command::InjectFault { fault, .. } => fault.validate(),
+			_ => Ok(()),
+		}
+	}
+
+	pub fn interval(&self) -> Option<Duration> {
+		match self {
+			Command::SetInterval { interval, .. } => Duration::try_from_secs_f64(*interval).ok(),
+			_ => None,
+		}
+	}
+
+	// Indexes of the targeted sensors, all of them when the command does not name one
+	pub fn targets<'a>(&self, sensors: impl Iterator<Item = &'a str>) -> Result<Vec<usize>, String> {
+		let sensor = match self {
+			Command::SetInterval { sensor, .. }
+			| Command::Calibrate { sensor, .. }
+			| Command::InjectFault { sensor, .. }
+			| Command::ClearFaults { sensor } => sensor.as_deref(),
+			Command::Reset | Command::Shutdown => None,
+		};
+
+		let targets: Vec<_> = sensors
+			.enumerate()
+			.filter(|(_, name)| sensor.is_none_or(|sensor| sensor.eq_ignore_ascii_case(name)))
+			.map(|(index, _)| index)
+			.collect();
+
+		match sensor {
+			Some(sensor) if targets.is_empty() => Err(format!("unknown sensor '{}'", sensor)),
+			_ => Ok(targets),
+		}
+	}
+}
+
+impl CommandMessage {
+	pub fn decode(payload: &str) -> Self {
+		match serde_json::from_str::<Request>(payload) {
+			Ok(request) => CommandMessage { id: request.id, command: Ok(request.command) },
+			Err(e) => {
+				let id = serde_json::from_str::<serde_json::Value>(payload)
+					.ok()
+					.and_then(|request| request.get("id")?.as_str().map(str::to_owned));
+				CommandMessage { id, command: Err(e.to_string()) }
+			}
+		}
+	}
+}
+
+impl CommandResponse {
+	pub fn new(device_id: &str, message: &CommandMessage, result: Result<(), String>) -> Self {
+		let (status, error) = match result {
+			Ok(()) => (CommandStatus::Ok, None),
+			Err(e) => (CommandStatus::Error, Some(e)),
+		};
+
+		CommandResponse {
+			id: message.id.clone(),
+			device_id: device_id.to_owned(),
+			command: message.command.as_ref().ok().map(|command| command.name().to_owned()),
+			status,
+			error,
+		}
+	}
+
+	pub fn encode(&self) -> String {
+		serde_json::to_string(self).unwrap()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_decode_commands() {
+		let message = CommandMessage::decode(r#"{"id": "42", "command": "set_interval", "sensor": "SPS30", "interval": 0.5}"#);
+		assert_eq!(message.id.as_deref(), Some("42"));
+		let command = message.command.expect("Command should be valid");
+		assert_eq!(command.name(), "set_interval");
+		assert_eq!(command.interval(), Some(Duration::from_millis(500)));
+		assert_eq!(command.targets(["SCD30", "sps30"].into_iter()), Ok(vec![1]));
+		assert_eq!(command.targets(["SCD30"].into_iter()), Err("unknown sensor 'SPS30'".to_owned()));
+
+		let command =
+			CommandMessage::decode(r#"{"command": "inject_fault", "fault": {"type": "dropout", "until": 30}}"#).command.unwrap();
+		assert!(command.validate().is_ok());
+		assert_eq!(command.targets(["SCD30", "SPS30"].into_iter()), Ok(vec![0, 1]));
+
+		let command = CommandMessage::decode(r#"{"command": "inject_fault", "fault": {"type": "spike"}}"#).command.unwrap();
+		assert_eq!(command.validate(), Err("spike faults require a magnitude".to_owned()));
+
+		let command = CommandMessage::decode(r#"{"command": "set_interval", "interval": 1e20}"#).command.unwrap();
+		assert_eq!(command.validate(), Err("interval must be at most 86400 seconds".to_owned()));
+		assert_eq!(command.interval(), None);
+
+		let command = CommandMessage::decode(r#"{"command": "inject_fault", "fault": {"type": "stuck", "duration": 1e300}}"#)
+			.command
+			.unwrap();
+		assert!(command.validate().is_err(), "Injected faults should be bounded like configured ones");
+
+		assert!(matches!(CommandMessage::decode(r#"{"command": "shutdown"}"#).command, Ok(Command::Shutdown)));
+	}
+
+	#[test]
+	fn test_command_responses() {
+		let message = CommandMessage::decode(r#"{"id": "7", "command": "reboot"}"#);
+		assert!(message.command.as_ref().is_err_and(|e| e.contains("unknown variant `reboot`")));
+
+		let response = CommandResponse::new("station-1", &message, Err("unsupported command".to_owned()));
+		assert_eq!(response.encode(), r#"{"id":"7","device_id":"station-1","status":"error","error":"unsupported command"}"#);
+
+		let message = CommandMessage::decode(r#"{"command": "reset"}"#);
+		let response = CommandResponse::new("station-1", &message, Ok(()));
+		assert_eq!(response.encode(), r#"{"device_id":"station-1","command":"reset","status":"ok"}"#);
+	}
+}
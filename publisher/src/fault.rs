@@ -0,0 +1,374 @@
+use crate::sensor::Sensor;
+use chrono::{DateTime, Utc};
+use rand::{Rng, RngCore};
+use serde::Deserialize;
+use std::time::Duration;
+
+// Fault times and offsets are bounded, so the timestamps they are added to stay in range
+const MAX_FAULT_SECONDS: f64 = 365.0 * 24.0 * 3600.0;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FaultConfig {
+	#[serde(rename = "type")]
+	kind: FaultKind,
+	probability: Option<f64>,
+	from: Option<f64>,
+	until: Option<f64>,
+	duration: Option<f64>,
+	magnitude: Option<f64>,
+	offset: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultKind {
+	Dropout,
+	Stuck,
+	Spike,
+	Saturate,
+	OutOfRange,
+	Nan,
+	Malformed,
+	ClockSkew,
+}
+
+impl FaultKind {
+	fn name(&self) -> &str {
+		match self {
+			FaultKind::Dropout => "dropout",
+			FaultKind::Stuck => "stuck",
+			FaultKind::Spike => "spike",
+			FaultKind::Saturate => "saturate",
+			FaultKind::OutOfRange => "out_of_range",
+			FaultKind::Nan => "nan",
+			FaultKind::Malformed => "malformed",
+			FaultKind::ClockSkew => "clock_skew",
+		}
+	}
+}
+
+pub enum Reading {
+	Value { at: DateTime<Utc>, value: f32 },
+	Malformed { at: DateTime<Utc>, value: f32 },
+	Dropped,
+}
+
+impl FaultConfig {
+	pub fn validate(&self) -> Result<(), String> {
+		if let Some(probability) = self.probability {
+			if !(0.0..=1.0).contains(&probability) {
+				return Err("probability must be between 0 and 1".to_owned());
+			}
+		}
+
+		let numbers = [
+			("from", self.from),
+			("until", self.until),
+			("duration", self.duration),
+			("magnitude", self.magnitude),
+			("offset", self.offset),
+		];
+		for (field, value) in numbers {
+			if value.is_some_and(|value| !value.is_finite()) {
+				return Err(format!("{} must be a finite number", field));
+			}
+		}
+
+		if self.from.is_some_and(|from| from < 0.0) {
+			return Err("from must not be negative".to_owned());
+		}
+
+		if let (Some(from), Some(until)) = (self.from, self.until) {
+			if until <= from {
+				return Err("until must be after from".to_owned());
+			}
+		}
+
+		let fields = [
+			("duration", self.duration, self.kind == FaultKind::Stuck),
+			("magnitude", self.magnitude, self.kind == FaultKind::Spike),
+			("offset", self.offset, self.kind == FaultKind::ClockSkew),
+		];
+
+		for (field, value, required) in fields {
+			match (value, required) {
+				(None, true) => return Err(format!("{} faults require a {}", self.kind.name(), field)),
+				(Some(_), false) => return Err(format!("{} is not used by {} faults", field, self.kind.name())),
+				_ => {}
+			}
+		}
+
+		if self.duration.is_some_and(|duration| duration <= 0.0) {
+			return Err("duration must be positive".to_owned());
+		}
+
+		if self.duration.is_some_and(|duration| duration > MAX_FAULT_SECONDS) {
+			return Err(format!("duration must be at most {} seconds", MAX_FAULT_SECONDS));
+		}
+
+		if self.offset.is_some_and(|offset| offset.abs() > MAX_FAULT_SECONDS) {
+			return Err(format!("offset must be at most {} seconds", MAX_FAULT_SECONDS));
+		}
+
+		if self.magnitude.is_some_and(|magnitude| magnitude.abs() > f32::MAX as f64) {
+			return Err("magnitude is too large".to_owned());
+		}
+
+		Ok(())
+	}
+
+	fn is_active(&self, elapsed: f64) -> bool {
+		self.from.is_none_or(|from| elapsed >= from) && self.until.is_none_or(|until| elapsed < until)
+	}
+}
+
+// Configured faults are timed from the first reading, injected faults from the moment they were injected
+pub struct FaultySensor {
+	sensor: Box<dyn Sensor>,
+	configured: Vec<FaultConfig>,
+	faults: Vec<(FaultConfig, Option<DateTime<Utc>>)>,
+	interval: Duration,
+	offset: f32,
+	start: Option<DateTime<Utc>>,
+	stuck: Option<(f32, DateTime<Utc>)>,
+}
+
+impl FaultySensor {
+	// The interval is the configured one, as a replayed sensor only knows its interval once it read a record
+	pub fn new(sensor: Box<dyn Sensor>, faults: Vec<FaultConfig>, interval: Duration) -> Self {
+		let active = faults.iter().cloned().map(|fault| (fault, None)).collect();
+
+		FaultySensor { sensor, configured: faults, faults: active, interval, offset: 0.0, start: None, stuck: None }
+	}
+
+	pub fn sensor(&self) -> &dyn Sensor {
+		self.sensor.as_ref()
+	}
+
+	pub fn set_read_interval(&mut self, interval: Duration) {
+		self.sensor.set_read_interval(interval);
+	}
+
+	pub fn calibrate(&mut self, offset: f32) {
+		self.offset = offset;
+	}
+
+	pub fn inject(&mut self, fault: FaultConfig, at: DateTime<Utc>) {
+		self.faults.push((fault, Some(at)));
+	}
+
+	pub fn clear_faults(&mut self) {
+		self.faults.clear();
+		self.stuck = None;
+	}
+
+	// Back to the configured interval and faults, as if the device had just been powered on
+	pub fn reset(&mut self) {
+		self.sensor.set_read_interval(self.interval);
+		self.faults = self.configured.iter().cloned().map(|fault| (fault, None)).collect();
+		self.offset = 0.0;
+		self.start = None;
+		self.stuck = None;
+	}
+
+	pub fn read(&mut self, at: DateTime<Utc>, rng: &mut dyn RngCore) -> Reading {
+		let start = *self.start.get_or_insert(at);
+		let mut value = self.sensor.sample(at, rng) + self.offset;
+		let mut reading_at = at;
+		let mut malformed = false;
+
+		match self.stuck {
+			Some((stuck_value, until)) if at < until => value = stuck_value,
+			_ => self.stuck = None,
+		}
+
+		for (fault, injected_at) in &self.faults {
+			let elapsed = (at - injected_at.unwrap_or(start)).num_milliseconds() as f64 / 1000.0;
+			if !fault.is_active(elapsed) || !rng.gen_bool(fault.probability.unwrap_or(1.0)) {
+				continue;
+			}
+
+			let (min, max) = self.sensor.value_range();
+			match fault.kind {
+				FaultKind::Dropout => return Reading::Dropped,
+				FaultKind::Stuck => {
+					if self.stuck.is_none() {
+						let until = seconds(fault.duration.unwrap_or(0.0)).and_then(|duration| at.checked_add_signed(duration));
+						self.stuck = Some((value, until.unwrap_or(DateTime::<Utc>::MAX_UTC)));
+					}
+				}
+				FaultKind::Spike => value += fault.magnitude.unwrap_or(0.0) as f32,
+				FaultKind::Saturate => value = if rng.gen_bool(0.5) { max } else { min },
+				FaultKind::OutOfRange => {
+					let excess = (max - min) * rng.gen_range(0.1..1.0);
+					value = if rng.gen_bool(0.5) { max + excess } else { min - excess };
+				}
+				FaultKind::Nan => value = f32::NAN,
+				FaultKind::Malformed => malformed = true,
+				FaultKind::ClockSkew => {
+					let skewed = seconds(fault.offset.unwrap_or(0.0)).and_then(|offset| reading_at.checked_add_signed(offset));
+					reading_at = skewed.unwrap_or(reading_at);
+				}
+			}
+		}
+
+		if malformed {
+			Reading::Malformed { at: reading_at, value }
+		} else {
+			Reading::Value { at: reading_at, value }
+		}
+	}
+}
+
+fn seconds(seconds: f64) -> Option<chrono::Duration> {
+	chrono::Duration::try_milliseconds((seconds * 1000.0) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::replay::ReplaySensor;
+	use crate::sensor::SPS30;
+	use rand::SeedableRng;
+	use rand_chacha::ChaCha8Rng;
+
+	fn start() -> DateTime<Utc> {
+		DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc)
+	}
+
+	fn readings(faults: &[&str], count: i64) -> Vec<Reading> {
+		let faults = faults
+			.iter()
+			.map(|fault| {
+				let config: FaultConfig = toml::from_str(fault).expect("Failed to parse fault");
+				config.validate().expect("Fault should be valid");
+				config
+			})
+			.collect();
+
+		let mut sensor = FaultySensor::new(Box::new(SPS30::new()), faults, SPS30::new().read_interval());
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		(0..count).map(|i| sensor.read(start() + chrono::Duration::seconds(i), &mut rng)).collect()
+	}
+
+	fn values(readings: &[Reading]) -> Vec<Option<f32>> {
+		readings
+			.iter()
+			.map(|reading| match reading {
+				Reading::Value { value, .. } => Some(*value),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_no_faults() {
+		let readings = readings(&[], 20);
+
+		for (i, reading) in readings.iter().enumerate() {
+			match reading {
+				Reading::Value { at, value } => {
+					assert_eq!(*at, start() + chrono::Duration::seconds(i as i64));
+					assert!((0.3..=1.0).contains(value), "Value {} out of range", value);
+				}
+				_ => panic!("Reading {} should be a clean value", i),
+			}
+		}
+	}
+
+	#[test]
+	fn test_dropout_window() {
+		let values = values(&readings(&["type = \"dropout\"\nfrom = 5\nuntil = 10"], 15));
+
+		assert!(values[..5].iter().all(Option::is_some));
+		assert!(values[5..10].iter().all(Option::is_none), "Sensor should be offline between 5s and 10s");
+		assert!(values[10..].iter().all(Option::is_some));
+	}
+
+	#[test]
+	fn test_stuck_value() {
+		let values = values(&readings(&["type = \"stuck\"\nfrom = 2\nuntil = 3\nduration = 5"], 10));
+
+		assert!(values[2..7].iter().all(|value| *value == values[2]), "Value should be frozen for 5 seconds: {:?}", values);
+		assert_ne!(values[7], values[2]);
+	}
+
+	#[test]
+	fn test_invalid_values() {
+		let nan = values(&readings(&["type = \"nan\"\nprobability = 1.0"], 5));
+		assert!(nan.iter().all(|value| value.is_some_and(f32::is_nan)));
+
+		let out_of_range = values(&readings(&["type = \"out_of_range\""], 50));
+		assert!(out_of_range.iter().all(|value| value.is_some_and(|value| !(0.3..=1.0).contains(&value))));
+
+		let saturated = values(&readings(&["type = \"saturate\""], 50));
+		assert!(saturated.iter().all(|value| *value == Some(0.3) || *value == Some(1.0)));
+	}
+
+	#[test]
+	fn test_clock_skew_and_malformed() {
+		let skewed = readings(&["type = \"clock_skew\"\noffset = -3600"], 1);
+		assert!(matches!(skewed[0], Reading::Value { at, .. } if at == start() - chrono::Duration::hours(1)));
+
+		let malformed = readings(&["type = \"malformed\"\nprobability = 0.5"], 100);
+		let count = malformed.iter().filter(|reading| matches!(reading, Reading::Malformed { .. })).count();
+		assert!((30..70).contains(&count), "About half the readings should be malformed, got {}", count);
+	}
+
+	#[test]
+	fn test_injected_faults_and_reset() {
+		let mut sensor = FaultySensor::new(Box::new(SPS30::new()), Vec::new(), SPS30::new().read_interval());
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		let at = |seconds| start() + chrono::Duration::seconds(seconds);
+
+		sensor.calibrate(100.0);
+		assert!(matches!(sensor.read(at(0), &mut rng), Reading::Value { value, .. } if value > 100.0));
+
+		let fault: FaultConfig = toml::from_str("type = \"dropout\"\nfrom = 5\nuntil = 10").unwrap();
+		sensor.inject(fault, at(10));
+		assert!(matches!(sensor.read(at(14), &mut rng), Reading::Value { .. }), "Injected faults are timed from the injection");
+		assert!(matches!(sensor.read(at(15), &mut rng), Reading::Dropped));
+
+		sensor.set_read_interval(Duration::from_secs(10));
+		sensor.reset();
+		assert_eq!(sensor.sensor().read_interval(), SPS30::new().read_interval());
+		assert!(matches!(sensor.read(at(15), &mut rng), Reading::Value { value, .. } if value < 100.0));
+	}
+
+	#[test]
+	fn test_reset_replay() {
+		let records = vec![(start(), 1.0), (start() + chrono::Duration::seconds(1), 2.0)];
+		let replay = ReplaySensor::new(Box::new(SPS30::new()), records, 1.0, true);
+		let mut sensor = FaultySensor::new(Box::new(replay), Vec::new(), Duration::from_secs(5));
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+		sensor.set_read_interval(Duration::from_secs(10));
+		sensor.reset();
+		sensor.read(start(), &mut rng);
+		assert_eq!(sensor.sensor().read_interval(), Duration::from_secs(1));
+		sensor.read(start(), &mut rng);
+		assert_eq!(sensor.sensor().read_interval(), Duration::from_secs(5), "The replay should loop at the configured interval");
+	}
+
+	#[test]
+	fn test_invalid_faults() {
+		let cases = [
+			("type = \"dropout\"\nprobability = 2.0", "probability must be between 0 and 1"),
+			("type = \"dropout\"\nfrom = 10\nuntil = 5", "until must be after from"),
+			("type = \"stuck\"", "stuck faults require a duration"),
+			("type = \"spike\"", "spike faults require a magnitude"),
+			("type = \"nan\"\noffset = 10", "offset is not used by nan faults"),
+			("type = \"stuck\"\nduration = nan", "duration must be a finite number"),
+			("type = \"stuck\"\nduration = 1e20", "duration must be at most 31536000 seconds"),
+			("type = \"clock_skew\"\noffset = 1e300", "offset must be at most 31536000 seconds"),
+			("type = \"clock_skew\"\noffset = -inf", "offset must be a finite number"),
+			("type = \"spike\"\nmagnitude = 1e300", "magnitude is too large"),
+		];
+
+		for (case, message) in cases {
+			let config: FaultConfig = toml::from_str(case).expect("Failed to parse fault");
+			assert_eq!(config.validate().expect_err("Fault should be invalid"), message);
+		}
+	}
+}
@@ -0,0 +1,430 @@
+use crate::clock::{Clock, ClockMode};
+use crate::command::{Command, CommandMessage, CommandResponse};
+use crate::fault::{FaultySensor, Reading};
+use crate::replay::ReplaySensor;
+use crate::scenario::Scenario;
+use crate::sensor::{self, Sensor};
+use crate::signal::ModeledSensor;
+use chrono::{DateTime, Utc};
+use common::message::SensorMessage;
+use common::mqtt::{AsyncMqttClient, ClientOptions, Message, MqttVersion, PublishProperties};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::{
+	error::Error,
+	time::{Duration, Instant},
+};
+use tokio::sync::mpsc;
+
+const DEFAULT_STATUS_TOPIC: &str = "devices/{id}/status";
+const DEFAULT_COMMAND_TOPIC: &str = "devices/{id}/commands";
+const DEFAULT_RESPONSE_TOPIC: &str = "devices/{id}/responses";
+
+pub struct Device {
+	client_id: String,
+	topic: String,
+	qos: i32,
+	sensors: Vec<FaultySensor>,
+	rng: ChaCha8Rng,
+	version: Option<MqttVersion>,
+	session_expiry: Option<u32>,
+	properties: PublishProperties,
+	status_topic: Option<String>,
+	command_topic: Option<String>,
+	response_topic: Option<String>,
+}
+
+impl Device {
+	pub fn new(client_id: &str, topic: &str, qos: i32, sensors: Vec<FaultySensor>, seed: u64) -> Self {
+		let mut rng = ChaCha8Rng::seed_from_u64(seed);
+		rng.set_stream(stream_id(client_id));
+
+		Device {
+			client_id: client_id.to_owned(),
+			topic: topic.replace("{id}", client_id),
+			qos,
+			sensors,
+			rng,
+			version: None,
+			session_expiry: None,
+			properties: PublishProperties::default(),
+			status_topic: None,
+			command_topic: None,
+			response_topic: None,
+		}
+	}
+
+	pub fn with_mqtt(mut self, version: Option<MqttVersion>, session_expiry: Option<u32>, properties: PublishProperties) -> Self {
+		self.version = version;
+		self.session_expiry = session_expiry;
+		self.properties = properties;
+		self
+	}
+
+	pub fn with_status_topic(mut self, topic: Option<&str>) -> Self {
+		self.status_topic = topic.map(str::to_owned);
+		self
+	}
+
+	pub fn with_command_topics(mut self, command_topic: Option<&str>, response_topic: Option<&str>) -> Self {
+		self.command_topic = command_topic.map(str::to_owned);
+		self.response_topic = response_topic.map(str::to_owned);
+		self
+	}
+
+	pub fn client_id(&self) -> &str {
+		&self.client_id
+	}
+
+	pub async fn run(self, mut clock: Box<dyn Clock>, end: Option<DateTime<Utc>>) -> Result<(), Box<dyn Error>> {
+		let mut options = ClientOptions::from_env(&self.client_id);
+		options.version = self.version.unwrap_or(options.version);
+		options.session_expiry = self.session_expiry.or(options.session_expiry);
+		let status_topic = self.status_topic.clone().or(options.status_topic).unwrap_or_else(|| DEFAULT_STATUS_TOPIC.to_owned());
+		options.status_topic = Some(status_topic).filter(|topic| !topic.is_empty());
+
+		let mqtt_client = AsyncMqttClient::with_options(&self.client_id, options)?;
+		mqtt_client.connect(Duration::from_secs(60), true).await?;
+		let client_id = self.client_id.clone();
+		let topic = self.topic.clone();
+		let qos = self.qos;
+		let properties = self.properties.clone();
+
+		// An empty command topic disables commands, like an empty status topic disables status messages
+		let command_topic = topic_or_default(self.command_topic.as_deref(), DEFAULT_COMMAND_TOPIC, &client_id);
+		let response_topic = topic_or_default(self.response_topic.as_deref(), DEFAULT_RESPONSE_TOPIC, &client_id);
+		let (commands, mut requests) = mpsc::unbounded_channel();
+		if let Some(command_topic) = &command_topic {
+			mqtt_client.route(command_topic, 1, move |request| _ = commands.send(request.clone())).await?;
+		}
+
+		let mut streams = self.into_streams();
+		let mut next_reads = vec![Some(clock.now()); streams.len()];
+		let mut sequence = 0;
+
+		while let Some((index, at)) =
+			next_reads.iter().enumerate().filter_map(|(index, at)| at.map(|at| (index, at))).min_by_key(|(_, at)| *at)
+		{
+			if end.is_some_and(|end| at >= end) {
+				break;
+			}
+
+			let delay = clock.delay_until(at);
+			tokio::select! {
+				biased;
+				Some(message) = mqtt_client.next_message(), if command_topic.is_some() => {
+					if !mqtt_client.handle(&message) {
+						eprintln!("Device {} has no handler for the message received on {}", client_id, message.topic());
+					}
+					continue;
+				}
+				Some(request) = requests.recv() => {
+					let message = CommandMessage::decode(&request.payload_str());
+					let result = message.command.clone().and_then(|command| {
+						command.validate()?;
+						println!("Device {} received the {} command", client_id, command.name());
+						execute(command, &mut streams, &mut next_reads, clock.now())
+					});
+					if let Err(e) = &result {
+						eprintln!("Device {} rejected a command: {}", client_id, e);
+					} else if matches!(message.command, Ok(Command::Reset)) {
+						sequence = 0;
+					}
+
+					let response = CommandResponse::new(&client_id, &message, result);
+					respond(&mqtt_client, &request, response_topic.as_deref(), &response).await;
+					if matches!(message.command, Ok(Command::Shutdown)) {
+						println!("Device {} is shutting down", client_id);
+						break;
+					}
+					continue;
+				}
+				_ = tokio::time::sleep(delay) => {}
+			}
+
+			let (faulty_sensor, rng) = &mut streams[index];
+			let reading = faulty_sensor.read(at, rng);
+			let sensor = faulty_sensor.sensor();
+			next_reads[index] = if sensor.is_exhausted() {
+				None
+			} else {
+				Some(at + chrono::Duration::from_std(sensor.read_interval())?)
+			};
+
+			sequence += 1;
+			let payload = match reading {
+				Reading::Value { at, value } => message(&client_id, sensor, at, value).with_sequence(sequence).encode(),
+				Reading::Malformed { at, value } => {
+					let payload = message(&client_id, sensor, at, value).with_sequence(sequence).encode();
+					payload.chars().take(payload.chars().count() / 2).collect()
+				}
+				Reading::Dropped => continue,
+			};
+
+			if let Err(e) = mqtt_client.publish_with(&topic, &payload, qos, &properties).await {
+				eprintln!("Failed to publish reading {} of {}: {}", sequence, client_id, e);
+			}
+		}
+
+		mqtt_client.disconnect().await?;
+		Ok(())
+	}
+
+	fn into_streams(self) -> Vec<(FaultySensor, ChaCha8Rng)> {
+		let mut rng = self.rng;
+
+		self.sensors
+			.into_iter()
+			.map(|sensor| (sensor, ChaCha8Rng::from_rng(&mut rng).expect("ChaCha8Rng seeding is infallible")))
+			.collect()
+	}
+}
+
+fn execute(
+	command: Command,
+	streams: &mut [(FaultySensor, ChaCha8Rng)],
+	next_reads: &mut [Option<DateTime<Utc>>],
+	now: DateTime<Utc>,
+) -> Result<(), String> {
+	let targets = command.targets(streams.iter().map(|(sensor, _)| sensor.sensor().name()))?;
+
+	for index in targets {
+		let (sensor, _) = &mut streams[index];
+		match &command {
+			Command::SetInterval { .. } => {
+				let interval = command.interval().unwrap_or_default();
+				sensor.set_read_interval(interval);
+				next_reads[index] =
+					next_reads[index].map(|_| now + chrono::Duration::from_std(interval).unwrap_or(chrono::Duration::MAX));
+			}
+			Command::Reset => {
+				sensor.reset();
+				next_reads[index] = Some(now).filter(|_| !sensor.sensor().is_exhausted());
+			}
+			Command::Calibrate { offset, .. } => sensor.calibrate(*offset),
+			Command::InjectFault { fault, .. } => sensor.inject(fault.clone(), now),
+			Command::ClearFaults { .. } => sensor.clear_faults(),
+			Command::Shutdown => {}
+		}
+	}
+
+	Ok(())
+}
+
+// Replies go to the response topic of the request when it sets one (MQTT v5), echoing its correlation data
+async fn respond(mqtt_client: &AsyncMqttClient, request: &Message, response_topic: Option<&str>, response: &CommandResponse) {
+	let request_properties = PublishProperties::from(request.properties());
+	let Some(topic) = request_properties.response_topic.as_deref().or(response_topic) else {
+		return;
+	};
+
+	let properties =
+		PublishProperties { correlation_data: request_properties.correlation_data.clone(), ..PublishProperties::default() };
+	if let Err(e) = mqtt_client.publish_with(topic, &response.encode(), 1, &properties).await {
+		eprintln!("Failed to publish the command response of {}: {}", response.device_id, e);
+	}
+}
+
+fn topic_or_default(topic: Option<&str>, default: &str, client_id: &str) -> Option<String> {
+	Some(topic.unwrap_or(default).replace("{id}", client_id)).filter(|topic| !topic.is_empty())
+}
+
+pub struct Fleet {
+	devices: Vec<Device>,
+	duration: Option<Duration>,
+	clock: ClockMode,
+	seed: u64,
+}
+
+impl Fleet {
+	pub fn from_scenario(scenario: &Scenario) -> Result<Self, Box<dyn Error>> {
+		let seed = scenario.seed.unwrap_or_else(rand::random);
+		let mut devices = Vec::new();
+
+		for device in &scenario.devices {
+			for index in 1..=device.count() {
+				let id = device.id.get_ref();
+				let client_id = if device.count() > 1 { format!("{}-{}", id, index) } else { id.clone() };
+
+				let mut sensors = Vec::new();
+				for config in &device.sensors {
+					let mut sensor = load_sensor(config.kind.get_ref())?;
+					let interval = config.interval().unwrap_or_else(|| sensor.read_interval());
+					if let Some(replay) = config.replay() {
+						sensor = Box::new(ReplaySensor::load(sensor, &scenario.resolve(&replay.file), replay)?);
+					}
+					let signals: Vec<_> = config.signals().map(|signal| signal.build()).collect();
+					if !signals.is_empty() {
+						sensor = Box::new(ModeledSensor::new(sensor, signals));
+					}
+					sensor.set_read_interval(interval);
+					sensors.push(FaultySensor::new(sensor, config.faults(), interval));
+				}
+
+				devices.push(
+					Device::new(&client_id, device.topic(), device.qos(), sensors, seed)
+						.with_mqtt(device.mqtt_version(), device.session_expiry(), device.properties())
+						.with_status_topic(device.status_topic())
+						.with_command_topics(device.command_topic(), device.response_topic()),
+				);
+			}
+		}
+
+		Ok(Fleet { devices, duration: scenario.duration(), clock: scenario.clock(), seed })
+	}
+
+	pub fn single(client_id: &str, topic: &str, sensors: Vec<Box<dyn Sensor>>) -> Self {
+		let seed = rand::random();
+		let sensors = sensors
+			.into_iter()
+			.map(|sensor| {
+				let interval = sensor.read_interval();
+				FaultySensor::new(sensor, Vec::new(), interval)
+			})
+			.collect();
+		Fleet { devices: vec![Device::new(client_id, topic, 1, sensors, seed)], duration: None, clock: ClockMode::Real, seed }
+	}
+
+	pub fn len(&self) -> usize {
+		self.devices.len()
+	}
+
+	pub fn seed(&self) -> u64 {
+		self.seed
+	}
+
+	pub async fn run(self) -> Result<(), Box<dyn Error>> {
+		let origin = Instant::now();
+		let start = self.clock.start().unwrap_or_else(Utc::now);
+		let end = self.duration.map(chrono::Duration::from_std).transpose()?.map(|duration| start + duration);
+		let clock = self.clock;
+
+		let handles: Vec<_> = self
+			.devices
+			.into_iter()
+			.map(|device| {
+				let clock = clock.build(origin, start);
+				tokio::spawn(async move {
+					let client_id = device.client_id().to_owned();
+					if let Err(e) = device.run(clock, end).await {
+						eprintln!("Device {} stopped: {}", client_id, e);
+					}
+				})
+			})
+			.collect();
+
+		for handle in handles {
+			handle.await.map_err(|_| "Device task panicked")?;
+		}
+
+		Ok(())
+	}
+}
+
+pub fn load_sensor(name: &str) -> Result<Box<dyn Sensor>, Box<dyn Error>> {
+	sensor::from_name(name)
+		.ok_or_else(|| format!("Unknown sensor '{}', available sensors: {}", name.trim(), sensor::CATALOG.join(", ")).into())
+}
+
+fn message(client_id: &str, sensor: &dyn Sensor, at: DateTime<Utc>, value: f32) -> SensorMessage {
+	let value: f64 = value.to_string().parse().unwrap_or(f64::NAN); // shortest f32 digits, f64::from adds noise
+	let scale = 10f64.powi(sensor.precision() as i32);
+	let value = (value * scale).round() / scale;
+
+	SensorMessage::new(client_id, sensor.name(), value, sensor.unit(), at)
+}
+
+fn stream_id(client_id: &str) -> u64 {
+	client_id.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_fleet_from_scenario() {
+		let scenario = Scenario::parse(
+			r#"
+duration = 60
+
+[[devices]]
+id = "station"
+topic = "devices/{id}/sensors"
+qos = 2
+count = 3
+sensors = [{ type = "SHT31_TEMPERATURE" }, { type = "SCD30", interval = 5 }]
+
+[[devices]]
+id = "tracker"
+sensors = [{ type = "NEO6M_LATITUDE" }, { type = "NEO6M_LONGITUDE" }]
+"#,
+		)
+		.expect("Failed to parse scenario");
+
+		let fleet = Fleet::from_scenario(&scenario).expect("Failed to build fleet");
+		assert_eq!(fleet.len(), 4);
+		assert_eq!(fleet.duration, Some(Duration::from_secs(60)));
+
+		let ids: Vec<_> = fleet.devices.iter().map(|device| device.client_id()).collect();
+		assert_eq!(ids, ["station-1", "station-2", "station-3", "tracker"]);
+		assert_eq!(fleet.devices[1].topic, "devices/station-2/sensors");
+		assert_eq!(fleet.devices[1].qos, 2);
+		assert_eq!(fleet.devices[3].topic, "sensors");
+		assert_eq!(fleet.devices[3].qos, 1);
+		assert_eq!(fleet.devices[0].sensors.len(), 2);
+	}
+
+	fn readings(scenario: &Scenario, count: usize) -> Vec<Vec<f32>> {
+		let fleet = Fleet::from_scenario(scenario).expect("Failed to build fleet");
+
+		fleet
+			.devices
+			.into_iter()
+			.flat_map(Device::into_streams)
+			.map(|(mut sensor, mut rng)| {
+				(0..count)
+					.filter_map(|_| match sensor.read(Utc::now(), &mut rng) {
+						Reading::Value { value, .. } => Some(value),
+						_ => None,
+					})
+					.collect()
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_fleet_seeded_readings() {
+		let scenario = Scenario::parse(
+			r#"
+seed = 7
+
+[[devices]]
+id = "station"
+count = 2
+sensors = [{ type = "SPS30" }, { type = "SCD30" }]
+"#,
+		)
+		.expect("Failed to parse scenario");
+
+		let first_run = readings(&scenario, 10);
+		assert_eq!(first_run, readings(&scenario, 10));
+		assert_ne!(first_run[0], first_run[2], "Devices should not share a value stream");
+		assert_eq!(&first_run[0][..5], GOLDEN_SPS30_READINGS);
+	}
+
+	#[test]
+	fn test_reading_message() {
+		let at = Utc::now();
+		let latitude = sensor::from_name("NEO6M_LATITUDE").unwrap();
+		let reading = message("tracker", latitude.as_ref(), at, -23.5558);
+
+		assert_eq!(reading.device_id, "tracker");
+		assert_eq!(reading.sensor, "NEO6M_LATITUDE");
+		assert_eq!(reading.value, -23.5558);
+		assert_eq!(reading.timestamp, Some(at));
+		assert_eq!(message("station", sensor::from_name("SPS30").unwrap().as_ref(), at, 0.69).value, 0.69);
+	}
+
+	const GOLDEN_SPS30_READINGS: &[f32] = &[0.69, 0.7, 0.71, 0.66, 0.62];
+}
@@ -1,22 +1,35 @@
+mod clock;
+mod command;
+mod fault;
+mod fleet;
+mod replay;
+mod scenario;
 mod sensor;
-use common::mqtt::MqttClient;
-use sensor::{Sensor, SPS30};
-use std::{error::Error, time::Duration};
+mod signal;
+use common::utils::dotenv;
+use fleet::Fleet;
+use scenario::Scenario;
+use std::{env, error::Error};
 
-fn main() -> Result<(), Box<dyn Error>> {
-	let mut mqtt_client = MqttClient::new("SPS30_PUBLISHER")?;
-	mqtt_client.connect(Duration::from_secs(60), true)?;
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+	let fleet = match env::args().nth(1) {
+		Some(path) => {
+			let scenario = Scenario::load(&path)?;
+			println!("Loaded scenario {}", scenario.name.as_deref().unwrap_or(&path));
+			Fleet::from_scenario(&scenario)?
+		}
+		None => {
+			let names = dotenv::get_var("SENSORS").unwrap_or_else(|_| "SPS30".to_owned());
+			let sensors = names
+				.split(',')
+				.filter(|name| !name.trim().is_empty())
+				.map(fleet::load_sensor)
+				.collect::<Result<Vec<_>, _>>()?;
+			Fleet::single("SPS30_PUBLISHER", "sensors", sensors)
+		}
+	};
 
-	let mut sensor = SPS30::new();
-
-	loop {
-		let value = sensor.read();
-		let payload = serde_json::json!({
-			"sensor": sensor.name(),
-			"value": format!("{:.2}", value),
-			"unit": sensor.unit()
-		});
-
-		mqtt_client.publish("sensors", payload.to_string().as_str(), 1).unwrap();
-	}
+	println!("Starting {} simulated devices with seed {}", fleet.len(), fleet.seed());
+	fleet.run().await
 }
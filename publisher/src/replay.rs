@@ -0,0 +1,244 @@
+use crate::sensor::Sensor;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::{collections::HashMap, error::Error, fs, path::Path, time::Duration};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReplayConfig {
+	pub file: String,
+	speed: Option<f64>,
+	#[serde(rename = "loop")]
+	looping: Option<bool>,
+}
+
+impl ReplayConfig {
+	pub fn validate(&self) -> Result<(), String> {
+		match self.speed {
+			Some(speed) if !speed.is_finite() || speed <= 0.0 => Err("speed must be a positive number".to_owned()),
+			_ => Ok(()),
+		}
+	}
+}
+
+pub struct ReplaySensor {
+	sensor: Box<dyn Sensor>,
+	records: Vec<(DateTime<Utc>, f32)>,
+	position: usize,
+	speed: f64,
+	looping: bool,
+	read_interval: Duration,
+	exhausted: bool,
+}
+
+impl ReplaySensor {
+	pub fn new(sensor: Box<dyn Sensor>, records: Vec<(DateTime<Utc>, f32)>, speed: f64, looping: bool) -> Self {
+		ReplaySensor { sensor, records, position: 0, speed, looping, read_interval: Duration::ZERO, exhausted: false }
+	}
+
+	pub fn load(sensor: Box<dyn Sensor>, path: &Path, config: &ReplayConfig) -> Result<Self, Box<dyn Error>> {
+		let content = fs::read_to_string(path).map_err(|e| format!("Failed to read recording {}: {}", path.display(), e))?;
+		let rows = match path.extension().and_then(|extension| extension.to_str()) {
+			Some("csv") => csv_rows(&content),
+			Some("jsonl" | "ndjson" | "json") => json_rows(&content),
+			_ => Err("unsupported format, expected a .csv or .jsonl file".to_owned()),
+		};
+		let records = rows
+			.and_then(|rows| records(rows, sensor.name()))
+			.map_err(|e| format!("Invalid recording {}: {}", path.display(), e))?;
+
+		Ok(Self::new(sensor, records, config.speed.unwrap_or(1.0), config.looping.unwrap_or(false)))
+	}
+}
+
+impl Sensor for ReplaySensor {
+	fn sample(&mut self, _at: DateTime<Utc>, _rng: &mut dyn RngCore) -> f32 {
+		let (recorded_at, value) = self.records[self.position];
+		self.position += 1;
+
+		let gap = match self.records.get(self.position) {
+			Some((next_at, _)) => (*next_at - recorded_at).to_std().unwrap_or(Duration::ZERO),
+			None if self.looping => {
+				self.position = 0;
+				self.sensor.read_interval()
+			}
+			None => {
+				self.position -= 1;
+				self.exhausted = true;
+				Duration::ZERO
+			}
+		};
+
+		self.read_interval = gap.div_f64(self.speed);
+		value
+	}
+
+	fn unit(&self) -> &str {
+		self.sensor.unit()
+	}
+
+	fn name(&self) -> &str {
+		self.sensor.name()
+	}
+
+	fn value_range(&self) -> (f32, f32) {
+		self.sensor.value_range()
+	}
+
+	fn read_interval(&self) -> Duration {
+		self.read_interval
+	}
+
+	fn set_read_interval(&mut self, read_interval: Duration) {
+		self.sensor.set_read_interval(read_interval);
+	}
+
+	fn precision(&self) -> usize {
+		self.sensor.precision()
+	}
+
+	fn is_exhausted(&self) -> bool {
+		self.exhausted
+	}
+}
+
+fn csv_rows(content: &str) -> Result<Vec<Map<String, Value>>, String> {
+	csv::Reader::from_reader(content.as_bytes())
+		.deserialize::<HashMap<String, String>>()
+		.map(|row| {
+			row.map(|row| row.into_iter().map(|(key, value)| (key, Value::String(value))).collect())
+				.map_err(|e| e.to_string())
+		})
+		.collect()
+}
+
+fn json_rows(content: &str) -> Result<Vec<Map<String, Value>>, String> {
+	content
+		.lines()
+		.enumerate()
+		.filter(|(_, line)| !line.trim().is_empty())
+		.map(|(index, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {}", index + 1, e)))
+		.collect()
+}
+
+fn records(rows: Vec<Map<String, Value>>, sensor: &str) -> Result<Vec<(DateTime<Utc>, f32)>, String> {
+	let mut records = Vec::new();
+
+	for (index, row) in rows.iter().enumerate() {
+		let name = row.get("sensor").or_else(|| row.get("name")).and_then(Value::as_str);
+		if name.is_some_and(|name| !name.eq_ignore_ascii_case(sensor)) {
+			continue;
+		}
+
+		let timestamp = row
+			.get("timestamp")
+			.or_else(|| row.get("createdAt"))
+			.and_then(parse_timestamp)
+			.ok_or_else(|| format!("record {} has a missing or invalid timestamp", index + 1))?;
+		let value = row
+			.get("value")
+			.and_then(parse_value)
+			.ok_or_else(|| format!("record {} has a missing or invalid value", index + 1))?;
+
+		records.push((timestamp, value));
+	}
+
+	if records.is_empty() {
+		return Err(format!("no {} readings found", sensor));
+	}
+
+	records.sort_by_key(|(timestamp, _)| *timestamp);
+	Ok(records)
+}
+
+fn parse_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+	match value {
+		Value::String(text) => match DateTime::parse_from_rfc3339(text) {
+			Ok(timestamp) => Some(timestamp.with_timezone(&Utc)),
+			Err(_) => parse_timestamp(&Value::from(text.parse::<f64>().ok()?)),
+		},
+		Value::Number(seconds) => DateTime::from_timestamp_millis((seconds.as_f64()? * 1000.0) as i64),
+		Value::Object(object) => object.get("$date").and_then(parse_timestamp),
+		_ => None,
+	}
+}
+
+fn parse_value(value: &Value) -> Option<f32> {
+	match value {
+		Value::String(text) => text.trim().parse().ok(),
+		Value::Number(number) => number.as_f64().map(|number| number as f32),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sensor::SPS30;
+	use rand::SeedableRng;
+	use rand_chacha::ChaCha8Rng;
+
+	fn replay(rows: Result<Vec<Map<String, Value>>, String>, speed: f64, looping: bool) -> ReplaySensor {
+		let records = records(rows.expect("Failed to parse recording"), "SPS30").expect("Invalid recording");
+		ReplaySensor::new(Box::new(SPS30::new()), records, speed, looping)
+	}
+
+	fn play(sensor: &mut ReplaySensor, count: usize) -> Vec<(f32, Duration)> {
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		(0..count).map(|_| (sensor.sample(Utc::now(), &mut rng), sensor.read_interval())).collect()
+	}
+
+	#[test]
+	fn test_replay_csv() {
+		let content = "timestamp,sensor,value\n\
+			2024-01-01T00:00:00Z,SPS30,0.52\n\
+			2024-01-01T00:00:00Z,SCD30,450\n\
+			2024-01-01T00:00:10Z,SPS30,0.61\n\
+			2024-01-01T00:00:15Z,SPS30,0.58\n";
+		let mut sensor = replay(csv_rows(content), 1.0, false);
+
+		let readings = play(&mut sensor, 3);
+		assert_eq!(readings, [(0.52, Duration::from_secs(10)), (0.61, Duration::from_secs(5)), (0.58, Duration::ZERO)]);
+		assert!(sensor.is_exhausted());
+	}
+
+	#[test]
+	fn test_replay_jsonl() {
+		let content = r#"
+{"name":"SPS30","value":0.7,"createdAt":{"$date":"2024-01-01T00:01:00Z"}}
+{"sensor":"SPS30","value":"0.5","unit":"μg/m³","timestamp":"2024-01-01T00:00:00Z"}
+{"value":0.6,"timestamp":1704067230}
+"#;
+		let mut sensor = replay(json_rows(content), 2.0, true);
+
+		let readings = play(&mut sensor, 4);
+		assert_eq!(
+			readings,
+			[
+				(0.5, Duration::from_secs(15)),
+				(0.6, Duration::from_secs(15)),
+				(0.7, Duration::from_millis(500)),
+				(0.5, Duration::from_secs(15)),
+			],
+			"Readings should be sorted, scaled by the speed and looped"
+		);
+		assert!(!sensor.is_exhausted());
+	}
+
+	#[test]
+	fn test_invalid_recordings() {
+		let cases = [
+			(csv_rows("timestamp,value\nyesterday,0.5\n"), "record 1 has a missing or invalid timestamp"),
+			(csv_rows("timestamp,value\n2024-01-01T00:00:00Z,high\n"), "record 1 has a missing or invalid value"),
+			(csv_rows("timestamp,sensor,value\n2024-01-01T00:00:00Z,SCD30,450\n"), "no SPS30 readings found"),
+		];
+
+		for (rows, message) in cases {
+			assert_eq!(records(rows.expect("Failed to parse recording"), "SPS30").expect_err("Recording should be invalid"), message);
+		}
+
+		assert!(json_rows("{\"value\": 1}\nnot json\n").expect_err("Recording should be invalid").starts_with("line 2:"));
+	}
+}
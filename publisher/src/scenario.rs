@@ -0,0 +1,569 @@
+use crate::clock::{self, ClockMode};
+use crate::fault::FaultConfig;
+use crate::replay::ReplayConfig;
+use crate::sensor;
+use crate::signal::SignalConfig;
+use chrono::{DateTime, Utc};
+use common::mqtt::{MqttVersion, PublishProperties};
+use serde::Deserialize;
+use std::{
+	collections::{BTreeMap, HashSet},
+	error::Error,
+	fmt, fs,
+	ops::Range,
+	path::{Path, PathBuf},
+	time::Duration,
+};
+use toml::{value::Datetime, Spanned};
+
+const MAX_SECONDS: f64 = 365.0 * 24.0 * 3600.0;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+	pub name: Option<String>,
+	pub seed: Option<u64>,
+	duration: Option<Spanned<f64>>,
+	clock: Option<ClockConfig>,
+	pub devices: Vec<DeviceConfig>,
+	#[serde(skip)]
+	base: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClockConfig {
+	mode: Spanned<String>,
+	speed: Option<Spanned<f64>>,
+	start: Option<Spanned<Datetime>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeviceConfig {
+	pub id: Spanned<String>,
+	topic: Option<String>,
+	qos: Option<Spanned<i32>>,
+	count: Option<Spanned<usize>>,
+	status_topic: Option<String>,
+	command_topic: Option<String>,
+	response_topic: Option<String>,
+	mqtt: Option<Spanned<MqttConfig>>,
+	pub sensors: Vec<SensorConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MqttConfig {
+	version: Option<Spanned<u8>>,
+	session_expiry: Option<u32>,
+	content_type: Option<String>,
+	message_expiry: Option<u32>,
+	topic_alias: Option<Spanned<u16>>,
+	#[serde(default)]
+	user_properties: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SensorConfig {
+	#[serde(rename = "type")]
+	pub kind: Spanned<String>,
+	interval: Option<Spanned<f64>>,
+	#[serde(default)]
+	signals: Vec<Spanned<SignalConfig>>,
+	#[serde(default)]
+	faults: Vec<Spanned<FaultConfig>>,
+	replay: Option<Spanned<ReplayConfig>>,
+}
+
+pub struct ScenarioError {
+	source: String,
+	location: Option<(usize, usize)>,
+	message: String,
+}
+
+impl Scenario {
+	pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+		let path = path.as_ref();
+		let source = path.display().to_string();
+		let content = fs::read_to_string(path)
+			.map_err(|e| ScenarioError { source: source.clone(), location: None, message: e.to_string() })?;
+
+		let mut scenario = Self::parse(&content).map_err(|e| ScenarioError { source, ..e })?;
+		scenario.base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+		Ok(scenario)
+	}
+
+	pub fn parse(content: &str) -> Result<Self, ScenarioError> {
+		let scenario: Scenario = toml::from_str(content).map_err(|e| ScenarioError::new(content, e.span(), e.message()))?;
+		scenario.validate().map_err(|(span, message)| ScenarioError::new(content, Some(span), &message))?;
+
+		Ok(scenario)
+	}
+
+	pub fn duration(&self) -> Option<Duration> {
+		self.duration.as_ref().map(|duration| Duration::from_secs_f64(*duration.get_ref()))
+	}
+
+	pub fn resolve(&self, file: &str) -> PathBuf {
+		self.base.join(file)
+	}
+
+	pub fn clock(&self) -> ClockMode {
+		let Some(clock) = &self.clock else {
+			return ClockMode::Real;
+		};
+		let start = clock.start.as_ref().and_then(|start| parse_datetime(start.get_ref()));
+
+		match clock.mode.get_ref().as_str() {
+			"accelerated" => ClockMode::Accelerated { speed: clock.speed.as_ref().map_or(1.0, |speed| *speed.get_ref()), start },
+			"virtual" => ClockMode::Virtual { start },
+			_ => ClockMode::Real,
+		}
+	}
+
+	fn validate(&self) -> Result<(), (Range<usize>, String)> {
+		if let Some(duration) = &self.duration {
+			check_seconds(duration, "duration")?;
+		}
+
+		if let Some(clock) = &self.clock {
+			clock.validate(self.duration.is_some())?;
+		}
+
+		if self.devices.is_empty() {
+			return Err((0..0, "scenario must declare at least one device".to_owned()));
+		}
+
+		let mut ids = HashSet::new();
+		for device in &self.devices {
+			if !ids.insert(device.id.get_ref()) {
+				return Err((device.id.span(), format!("duplicate device id '{}'", device.id.get_ref())));
+			}
+
+			if let Some(qos) = &device.qos {
+				if !(0..=2).contains(qos.get_ref()) {
+					return Err((qos.span(), format!("invalid qos {}, expected 0, 1 or 2", qos.get_ref())));
+				}
+			}
+
+			if let Some(count) = &device.count {
+				if *count.get_ref() == 0 {
+					return Err((count.span(), "device count must be at least 1".to_owned()));
+				}
+			}
+
+			if let Some(mqtt) = &device.mqtt {
+				mqtt.get_ref().validate(mqtt.span(), MqttVersion::from_env())?;
+			}
+
+			if device.sensors.is_empty() {
+				return Err((device.id.span(), format!("device '{}' has no sensors", device.id.get_ref())));
+			}
+
+			for sensor in &device.sensors {
+				if sensor::from_name(sensor.kind.get_ref()).is_none() {
+					return Err((
+						sensor.kind.span(),
+						format!(
+							"unknown sensor '{}', available sensors: {}",
+							sensor.kind.get_ref(),
+							sensor::CATALOG.join(", ")
+						),
+					));
+				}
+
+				if let Some(interval) = &sensor.interval {
+					check_seconds(interval, "interval")?;
+				}
+
+				for signal in &sensor.signals {
+					signal.get_ref().validate().map_err(|message| (signal.span(), message))?;
+				}
+
+				for fault in &sensor.faults {
+					fault.get_ref().validate().map_err(|message| (fault.span(), message))?;
+				}
+
+				if let Some(replay) = &sensor.replay {
+					replay.get_ref().validate().map_err(|message| (replay.span(), message))?;
+					if !sensor.signals.is_empty() {
+						return Err((replay.span(), "replayed sensors can not use signals".to_owned()));
+					}
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl ClockConfig {
+	fn validate(&self, has_duration: bool) -> Result<(), (Range<usize>, String)> {
+		let mode = self.mode.get_ref().as_str();
+		match mode {
+			"real" | "accelerated" | "virtual" => {}
+			_ => {
+				return Err((
+					self.mode.span(),
+					format!("unknown clock mode '{}', expected real, accelerated or virtual", mode),
+				))
+			}
+		}
+
+		match (&self.speed, mode) {
+			(Some(speed), "accelerated") if !(clock::MIN_SPEED..=clock::MAX_SPEED).contains(speed.get_ref()) => {
+				return Err((speed.span(), format!("speed must be between {} and {}", clock::MIN_SPEED, clock::MAX_SPEED)))
+			}
+			(Some(_), "accelerated") => {}
+			(None, "accelerated") => return Err((self.mode.span(), "accelerated clock requires a speed".to_owned())),
+			(Some(speed), _) => return Err((speed.span(), "speed is only used by the accelerated clock".to_owned())),
+			(None, _) => {}
+		}
+
+		if let Some(start) = &self.start {
+			if mode == "real" {
+				return Err((start.span(), "the real clock always starts at the current time".to_owned()));
+			}
+			if parse_datetime(start.get_ref()).is_none() {
+				return Err((start.span(), "start must be a date time with an offset, like 2024-01-01T00:00:00Z".to_owned()));
+			}
+		}
+
+		if mode == "virtual" && !has_duration {
+			return Err((self.mode.span(), "virtual clock requires a scenario duration".to_owned()));
+		}
+
+		Ok(())
+	}
+}
+
+impl MqttConfig {
+	// Devices without a version use the one from MQTT_VERSION, which decides whether v5 fields are allowed
+	fn validate(&self, span: Range<usize>, default_version: MqttVersion) -> Result<(), (Range<usize>, String)> {
+		if let Some(version) = &self.version {
+			if MqttVersion::from_number(*version.get_ref()).is_none() {
+				return Err((version.span(), format!("invalid mqtt version {}, expected 3 or 5", version.get_ref())));
+			}
+		}
+
+		let v5_field = [
+			("session_expiry", self.session_expiry.is_some()),
+			("content_type", self.content_type.is_some()),
+			("message_expiry", self.message_expiry.is_some()),
+			("topic_alias", self.topic_alias.is_some()),
+			("user_properties", !self.user_properties.is_empty()),
+		]
+		.into_iter()
+		.find_map(|(field, set)| set.then_some(field));
+
+		if let Some(field) = v5_field {
+			let version = self.version.as_ref().and_then(|version| MqttVersion::from_number(*version.get_ref()));
+			if version.unwrap_or(default_version) != MqttVersion::V5 {
+				return Err((span, format!("{} requires mqtt version 5", field)));
+			}
+		}
+
+		if let Some(alias) = &self.topic_alias {
+			if *alias.get_ref() == 0 {
+				return Err((alias.span(), "topic_alias must be at least 1".to_owned()));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl DeviceConfig {
+	pub fn topic(&self) -> &str {
+		self.topic.as_deref().unwrap_or("sensors")
+	}
+
+	pub fn qos(&self) -> i32 {
+		self.qos.as_ref().map_or(1, |qos| *qos.get_ref())
+	}
+
+	pub fn count(&self) -> usize {
+		self.count.as_ref().map_or(1, |count| *count.get_ref())
+	}
+
+	pub fn status_topic(&self) -> Option<&str> {
+		self.status_topic.as_deref()
+	}
+
+	pub fn command_topic(&self) -> Option<&str> {
+		self.command_topic.as_deref()
+	}
+
+	pub fn response_topic(&self) -> Option<&str> {
+		self.response_topic.as_deref()
+	}
+
+	pub fn mqtt_version(&self) -> Option<MqttVersion> {
+		let version = self.mqtt.as_ref()?.get_ref().version.as_ref()?;
+		MqttVersion::from_number(*version.get_ref())
+	}
+
+	pub fn session_expiry(&self) -> Option<u32> {
+		self.mqtt.as_ref()?.get_ref().session_expiry
+	}
+
+	pub fn properties(&self) -> PublishProperties {
+		let Some(mqtt) = &self.mqtt else {
+			return PublishProperties::default();
+		};
+		let mqtt = mqtt.get_ref();
+
+		PublishProperties {
+			user_properties: mqtt.user_properties.clone().into_iter().collect(),
+			content_type: mqtt.content_type.clone(),
+			message_expiry: mqtt.message_expiry,
+			topic_alias: mqtt.topic_alias.as_ref().map(|alias| *alias.get_ref()),
+			..PublishProperties::default()
+		}
+	}
+}
+
+impl SensorConfig {
+	pub fn interval(&self) -> Option<Duration> {
+		self.interval.as_ref().map(|interval| Duration::from_secs_f64(*interval.get_ref()))
+	}
+
+	pub fn signals(&self) -> impl Iterator<Item = &SignalConfig> {
+		self.signals.iter().map(Spanned::get_ref)
+	}
+
+	pub fn replay(&self) -> Option<&ReplayConfig> {
+		self.replay.as_ref().map(Spanned::get_ref)
+	}
+
+	pub fn faults(&self) -> Vec<FaultConfig> {
+		self.faults.iter().map(|fault| fault.get_ref().clone()).collect()
+	}
+}
+
+fn parse_datetime(datetime: &Datetime) -> Option<DateTime<Utc>> {
+	DateTime::parse_from_rfc3339(&datetime.to_string()).ok().map(|datetime| datetime.with_timezone(&Utc))
+}
+
+fn check_positive(value: &Spanned<f64>, field: &str) -> Result<(), (Range<usize>, String)> {
+	if value.get_ref().is_finite() && *value.get_ref() > 0.0 {
+		Ok(())
+	} else {
+		Err((value.span(), format!("{} must be a positive number", field)))
+	}
+}
+
+fn check_seconds(value: &Spanned<f64>, field: &str) -> Result<(), (Range<usize>, String)> {
+	check_positive(value, field)?;
+	if *value.get_ref() > MAX_SECONDS {
+		return Err((value.span(), format!("{} must be at most {} seconds", field, MAX_SECONDS)));
+	}
+
+	Ok(())
+}
+
+impl ScenarioError {
+	fn new(content: &str, span: Option<Range<usize>>, message: &str) -> Self {
+		let location = span.map(|span| {
+			let before = &content[..span.start.min(content.len())];
+			let line = before.matches('\n').count() + 1;
+			let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+			(line, column)
+		});
+
+		ScenarioError { source: "scenario".to_owned(), location, message: message.trim().to_owned() }
+	}
+}
+
+impl fmt::Display for ScenarioError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.location {
+			Some((line, column)) => write!(f, "{}:{}:{}: {}", self.source, line, column, self.message),
+			None => write!(f, "{}: {}", self.source, self.message),
+		}
+	}
+}
+
+impl fmt::Debug for ScenarioError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(self, f)
+	}
+}
+
+impl Error for ScenarioError {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_scenario() {
+		let scenario = Scenario::parse(
+			r#"
+name = "smoke"
+seed = 42
+duration = 30
+clock = { mode = "accelerated", speed = 60, start = 2024-01-01T00:00:00Z }
+
+[[devices]]
+id = "station"
+topic = "devices/{id}/sensors"
+qos = 0
+count = 2
+status_topic = "devices/{id}/availability"
+command_topic = "devices/{id}/downlink"
+response_topic = ""
+mqtt = { version = 5, content_type = "application/json", message_expiry = 300, user_properties = { firmware = "2.1.0" } }
+sensors = [
+	{ type = "SPS30", interval = 0.5 },
+	{ type = "SCD30", signals = [{ type = "constant", value = 450.0 }, { type = "noise", std_dev = 5.0 }] },
+	{ type = "BH1750", faults = [{ type = "dropout", probability = 0.1 }, { type = "stuck", from = 60, duration = 30 }] },
+]
+"#,
+		)
+		.expect("Failed to parse scenario");
+
+		assert_eq!(scenario.name.as_deref(), Some("smoke"));
+		assert_eq!(scenario.seed, Some(42));
+		assert_eq!(scenario.duration(), Some(Duration::from_secs(30)));
+		assert!(matches!(
+			scenario.clock(),
+			ClockMode::Accelerated { speed, start: Some(start) } if speed == 60.0 && start.to_rfc3339() == "2024-01-01T00:00:00+00:00"
+		));
+
+		let device = &scenario.devices[0];
+		assert_eq!(device.topic(), "devices/{id}/sensors");
+		assert_eq!(device.qos(), 0);
+		assert_eq!(device.count(), 2);
+		assert_eq!(device.status_topic(), Some("devices/{id}/availability"));
+		assert_eq!(device.command_topic(), Some("devices/{id}/downlink"));
+		assert_eq!(device.response_topic(), Some(""));
+		assert_eq!(device.mqtt_version(), Some(MqttVersion::V5));
+		let properties = device.properties();
+		assert_eq!(properties.content_type.as_deref(), Some("application/json"));
+		assert_eq!(properties.message_expiry, Some(300));
+		assert_eq!(properties.user_property("firmware"), Some("2.1.0"));
+		assert_eq!(device.sensors[0].interval(), Some(Duration::from_millis(500)));
+		assert_eq!(device.sensors[1].interval(), None);
+		assert_eq!(device.sensors[0].signals().count(), 0);
+		assert!(matches!(
+			device.sensors[1].signals().collect::<Vec<_>>()[..],
+			[SignalConfig::Constant { value }, SignalConfig::Noise { .. }] if *value == 450.0
+		));
+		assert_eq!(device.sensors[1].faults().len(), 0);
+		assert_eq!(device.sensors[2].faults().len(), 2);
+	}
+
+	#[test]
+	fn test_scenario_defaults() {
+		let scenario = Scenario::parse(
+			r#"
+[[devices]]
+id = "station"
+sensors = [{ type = "SPS30" }]
+"#,
+		)
+		.expect("Failed to parse scenario");
+
+		assert_eq!(scenario.seed, None);
+		assert_eq!(scenario.duration(), None);
+		assert!(matches!(scenario.clock(), ClockMode::Real));
+		assert_eq!(scenario.devices[0].topic(), "sensors");
+		assert_eq!(scenario.devices[0].qos(), 1);
+		assert_eq!(scenario.devices[0].count(), 1);
+		assert_eq!(scenario.devices[0].status_topic(), None);
+		assert_eq!(scenario.devices[0].command_topic(), None);
+		assert_eq!(scenario.devices[0].mqtt_version(), None);
+		assert!(scenario.devices[0].properties().is_empty());
+	}
+
+	#[test]
+	fn test_mqtt_default_version() {
+		let config: MqttConfig = toml::from_str("session_expiry = 3600").unwrap();
+		assert!(config.validate(0..0, MqttVersion::V5).is_ok(), "v5 fields should be allowed when MQTT_VERSION is 5");
+		assert!(config.validate(0..0, MqttVersion::V3).is_err());
+
+		let config: MqttConfig = toml::from_str("version = 3
+session_expiry = 3600").unwrap();
+		assert!(config.validate(0..0, MqttVersion::V5).is_err(), "The device version should override MQTT_VERSION");
+	}
+
+	#[test]
+	fn test_scenario_error_location() {
+		let cases = [
+			("[[devices]]\nid = \"a\"\nsensors = [{ type = \"DHT22\" }]\n", (3, 21), "unknown sensor 'DHT22'"),
+			("[[devices]]\nid = \"a\"\nqos = 3\nsensors = [{ type = \"SPS30\" }]\n", (3, 7), "invalid qos 3"),
+			("[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\", interval = -1 }]\n", (3, 41), "interval must be"),
+			(
+				"[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\", interval = 1e30 }]\n",
+				(3, 41),
+				"interval must be at most 31536000 seconds",
+			),
+			("duration = 1e30\n[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\" }]\n", (1, 12), "duration must be at most"),
+			("[[devices]]\nid = \"a\"\nsensors = []\n", (2, 6), "has no sensors"),
+			(
+				"[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\" }]\n\n[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\" }]\n",
+				(6, 6),
+				"duplicate device id 'a'",
+			),
+			("[[devices]]\nid = \"a\"\nrate = 2\nsensors = [{ type = \"SPS30\" }]\n", (3, 1), "unknown field `rate`"),
+			(
+				"[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\", signals = [{ type = \"noise\", std_dev = -1 }] }]\n",
+				(3, 41),
+				"std_dev must not be negative",
+			),
+			(
+				"[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\", signals = [{ type = \"square\" }] }]\n",
+				(3, 50),
+				"unknown variant `square`",
+			),
+			(
+				"[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\", faults = [{ type = \"spike\" }] }]\n",
+				(3, 40),
+				"spike faults require a magnitude",
+			),
+			(
+				"[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\", replay = { file = \"a.csv\", speed = 0 } }]\n",
+				(3, 39),
+				"speed must be a positive number",
+			),
+			(
+				"[[devices]]\nid = \"a\"\nmqtt = { version = 4 }\nsensors = [{ type = \"SPS30\" }]\n",
+				(3, 20),
+				"invalid mqtt version 4",
+			),
+			(
+				"[[devices]]\nid = \"a\"\nmqtt = { content_type = \"application/json\" }\nsensors = [{ type = \"SPS30\" }]\n",
+				(3, 8),
+				"content_type requires mqtt version 5",
+			),
+			("clock = { mode = \"warp\" }\n[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\" }]\n", (1, 18), "unknown clock mode"),
+			("clock = { mode = \"virtual\" }\n[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\" }]\n", (1, 18), "requires a scenario duration"),
+			("clock = { mode = \"accelerated\", speed = 0 }\n[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\" }]\n", (1, 41), "speed must be"),
+			(
+				"clock = { mode = \"accelerated\", speed = 1e30 }\n[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\" }]\n",
+				(1, 41),
+				"speed must be between 0.000001 and 1000000",
+			),
+			(
+				"clock = { mode = \"accelerated\", speed = 1e-300 }\n[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\" }]\n",
+				(1, 41),
+				"speed must be between 0.000001 and 1000000",
+			),
+			(
+				"duration = 1\nclock = { mode = \"virtual\", start = 2024-01-01T00:00:00 }\n[[devices]]\nid = \"a\"\nsensors = [{ type = \"SPS30\" }]\n",
+				(2, 37),
+				"start must be a date time with an offset",
+			),
+		];
+
+		for (content, location, message) in cases {
+			let error = Scenario::parse(content).expect_err("Scenario should be invalid");
+			assert_eq!(error.location, Some(location), "Wrong location for: {}", error);
+			assert!(error.to_string().contains(message), "Unexpected error: {}", error);
+		}
+	}
+}
@@ -0,0 +1,67 @@
+use super::{random_walk, Sensor};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use std::time::Duration;
+
+pub struct BH1750 {
+	last_read: f32,
+	read_interval: Duration,
+} // value range: 1lx - 65535lx / Read interval: 1s
+
+impl BH1750 {
+	const VALUE_RANGE: (f32, f32) = (1.0, 65535.0);
+	const READ_INTERVAL: u32 = 1;
+
+	pub fn new() -> Self {
+		BH1750 { last_read: 300.0, read_interval: Duration::from_secs(Self::READ_INTERVAL.into()) }
+	}
+}
+
+impl Sensor for BH1750 {
+	fn sample(&mut self, _at: DateTime<Utc>, rng: &mut dyn RngCore) -> f32 {
+		self.last_read = random_walk(rng, self.last_read, 10.0, Self::VALUE_RANGE);
+
+		self.last_read
+	}
+
+	fn unit(&self) -> &str {
+		"lx"
+	}
+
+	fn name(&self) -> &str {
+		"BH1750"
+	}
+
+	fn value_range(&self) -> (f32, f32) {
+		Self::VALUE_RANGE
+	}
+
+	fn read_interval(&self) -> Duration {
+		self.read_interval
+	}
+
+	fn set_read_interval(&mut self, read_interval: Duration) {
+		self.read_interval = read_interval;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::SeedableRng;
+	use rand_chacha::ChaCha8Rng;
+
+	#[test]
+	fn test_bh1750_sensor_data() {
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		let mut sensor = BH1750::new();
+		assert_eq!(sensor.name(), "BH1750");
+		assert_eq!(sensor.unit(), "lx");
+		let read_value = sensor.sample(Utc::now(), &mut rng);
+		assert!(
+			read_value >= BH1750::VALUE_RANGE.0 && read_value <= BH1750::VALUE_RANGE.1,
+			"Sensor value out of range: {}",
+			read_value
+		);
+	}
+}
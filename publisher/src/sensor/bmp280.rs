@@ -0,0 +1,67 @@
+use super::{random_walk, Sensor};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use std::time::Duration;
+
+pub struct BMP280 {
+	last_read: f32,
+	read_interval: Duration,
+} // value range: 300hPa - 1100hPa / Read interval: 1s
+
+impl BMP280 {
+	const VALUE_RANGE: (f32, f32) = (300.0, 1100.0);
+	const READ_INTERVAL: u32 = 1;
+
+	pub fn new() -> Self {
+		BMP280 { last_read: 1013.25, read_interval: Duration::from_secs(Self::READ_INTERVAL.into()) }
+	}
+}
+
+impl Sensor for BMP280 {
+	fn sample(&mut self, _at: DateTime<Utc>, rng: &mut dyn RngCore) -> f32 {
+		self.last_read = random_walk(rng, self.last_read, 0.1, Self::VALUE_RANGE);
+
+		self.last_read
+	}
+
+	fn unit(&self) -> &str {
+		"hPa"
+	}
+
+	fn name(&self) -> &str {
+		"BMP280"
+	}
+
+	fn value_range(&self) -> (f32, f32) {
+		Self::VALUE_RANGE
+	}
+
+	fn read_interval(&self) -> Duration {
+		self.read_interval
+	}
+
+	fn set_read_interval(&mut self, read_interval: Duration) {
+		self.read_interval = read_interval;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::SeedableRng;
+	use rand_chacha::ChaCha8Rng;
+
+	#[test]
+	fn test_bmp280_sensor_data() {
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		let mut sensor = BMP280::new();
+		assert_eq!(sensor.name(), "BMP280");
+		assert_eq!(sensor.unit(), "hPa");
+		let read_value = sensor.sample(Utc::now(), &mut rng);
+		assert!(
+			read_value >= BMP280::VALUE_RANGE.0 && read_value <= BMP280::VALUE_RANGE.1,
+			"Sensor value out of range: {}",
+			read_value
+		);
+	}
+}
@@ -0,0 +1,92 @@
+mod bh1750;
+mod bmp280;
+mod mpu6050;
+mod neo6m;
+mod scd30;
+mod sht31;
+mod sps30;
+
+pub use bh1750::BH1750;
+pub use bmp280::BMP280;
+pub use mpu6050::{Axis, MPU6050};
+pub use neo6m::{Coordinate, NEO6M};
+pub use scd30::SCD30;
+pub use sht31::{Measurement, SHT31};
+pub use sps30::SPS30;
+
+use common::utils::RoundToTwoDecimals;
+use rand::{Rng, RngCore};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+pub trait Sensor: Send {
+	fn sample(&mut self, at: DateTime<Utc>, rng: &mut dyn RngCore) -> f32;
+	fn unit(&self) -> &str;
+	fn name(&self) -> &str;
+	fn value_range(&self) -> (f32, f32);
+	fn read_interval(&self) -> Duration;
+	fn set_read_interval(&mut self, read_interval: Duration);
+
+	fn precision(&self) -> usize {
+		2
+	}
+
+	fn is_exhausted(&self) -> bool {
+		false
+	}
+}
+
+pub const CATALOG: [&str; 11] = [
+	"SPS30",
+	"SHT31_TEMPERATURE",
+	"SHT31_HUMIDITY",
+	"SCD30",
+	"BMP280",
+	"BH1750",
+	"NEO6M_LATITUDE",
+	"NEO6M_LONGITUDE",
+	"MPU6050_X",
+	"MPU6050_Y",
+	"MPU6050_Z",
+];
+
+pub fn from_name(name: &str) -> Option<Box<dyn Sensor>> {
+	let sensor: Box<dyn Sensor> = match name.trim().to_uppercase().as_str() {
+		"SPS30" => Box::new(SPS30::new()),
+		"SHT31_TEMPERATURE" => Box::new(SHT31::new(Measurement::Temperature)),
+		"SHT31_HUMIDITY" => Box::new(SHT31::new(Measurement::Humidity)),
+		"SCD30" => Box::new(SCD30::new()),
+		"BMP280" => Box::new(BMP280::new()),
+		"BH1750" => Box::new(BH1750::new()),
+		"NEO6M_LATITUDE" => Box::new(NEO6M::new(Coordinate::Latitude)),
+		"NEO6M_LONGITUDE" => Box::new(NEO6M::new(Coordinate::Longitude)),
+		"MPU6050_X" => Box::new(MPU6050::new(Axis::X)),
+		"MPU6050_Y" => Box::new(MPU6050::new(Axis::Y)),
+		"MPU6050_Z" => Box::new(MPU6050::new(Axis::Z)),
+		_ => return None,
+	};
+
+	Some(sensor)
+}
+
+fn random_walk(rng: &mut dyn RngCore, last_read: f32, max_step: f32, range: (f32, f32)) -> f32 {
+	let change: f32 = rng.gen_range(-max_step..max_step);
+
+	(last_read + change).clamp(range.0, range.1).round_to_two_decimals()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_catalog_lookup() {
+		for name in CATALOG {
+			let sensor = from_name(name).unwrap_or_else(|| panic!("Sensor {} is missing from the catalog", name));
+			assert_eq!(sensor.name(), name);
+		}
+
+		assert_eq!(from_name(" sht31_humidity ").map(|sensor| sensor.unit().to_owned()), Some("%RH".to_owned()));
+		assert!(from_name("DHT22").is_none());
+	}
+}
@@ -0,0 +1,82 @@
+use super::Sensor;
+use chrono::{DateTime, Utc};
+use common::utils::RoundToTwoDecimals;
+use rand::{Rng, RngCore};
+use std::time::Duration;
+
+pub enum Axis {
+	X,
+	Y,
+	Z,
+}
+
+pub struct MPU6050 {
+	axis: Axis,
+	read_interval: Duration,
+} // value range: -2g - 2g / Read interval: 1s
+
+impl MPU6050 {
+	const VALUE_RANGE: (f32, f32) = (-2.0, 2.0);
+	const VIBRATION: f32 = 0.02;
+	const READ_INTERVAL: u32 = 1;
+
+	pub fn new(axis: Axis) -> Self {
+		MPU6050 { axis, read_interval: Duration::from_secs(Self::READ_INTERVAL.into()) }
+	}
+
+	fn resting_value(&self) -> f32 {
+		match self.axis {
+			Axis::X | Axis::Y => 0.0,
+			Axis::Z => 1.0,
+		}
+	}
+}
+
+impl Sensor for MPU6050 {
+	fn sample(&mut self, _at: DateTime<Utc>, rng: &mut dyn RngCore) -> f32 {
+		let vibration: f32 = rng.gen_range(-Self::VIBRATION..Self::VIBRATION);
+
+		(self.resting_value() + vibration).clamp(Self::VALUE_RANGE.0, Self::VALUE_RANGE.1).round_to_two_decimals()
+	}
+
+	fn unit(&self) -> &str {
+		"g"
+	}
+
+	fn name(&self) -> &str {
+		match self.axis {
+			Axis::X => "MPU6050_X",
+			Axis::Y => "MPU6050_Y",
+			Axis::Z => "MPU6050_Z",
+		}
+	}
+
+	fn value_range(&self) -> (f32, f32) {
+		Self::VALUE_RANGE
+	}
+
+	fn read_interval(&self) -> Duration {
+		self.read_interval
+	}
+
+	fn set_read_interval(&mut self, read_interval: Duration) {
+		self.read_interval = read_interval;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::SeedableRng;
+	use rand_chacha::ChaCha8Rng;
+
+	#[test]
+	fn test_mpu6050_sensor_data() {
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		let mut sensor = MPU6050::new(Axis::Z);
+		assert_eq!(sensor.name(), "MPU6050_Z");
+		assert_eq!(sensor.unit(), "g");
+		let read_value = sensor.sample(Utc::now(), &mut rng);
+		assert!((read_value - 1.0).abs() < 0.03, "Resting Z axis should read about 1g, got {}", read_value);
+	}
+}
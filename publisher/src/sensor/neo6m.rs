@@ -0,0 +1,94 @@
+use super::Sensor;
+use chrono::{DateTime, Utc};
+use rand::{Rng, RngCore};
+use std::time::Duration;
+
+pub enum Coordinate {
+	Latitude,
+	Longitude,
+}
+
+pub struct NEO6M {
+	coordinate: Coordinate,
+	last_read: f32,
+	read_interval: Duration,
+} // value range: -90° - 90°, -180° - 180° / Read interval: 1s
+
+impl NEO6M {
+	const LATITUDE_RANGE: (f32, f32) = (-90.0, 90.0);
+	const LONGITUDE_RANGE: (f32, f32) = (-180.0, 180.0);
+	const START_POSITION: (f32, f32) = (-23.5558, -46.7339);
+	const MAX_STEP: f32 = 0.0001;
+	const READ_INTERVAL: u32 = 1;
+
+	pub fn new(coordinate: Coordinate) -> Self {
+		let last_read = match coordinate {
+			Coordinate::Latitude => Self::START_POSITION.0,
+			Coordinate::Longitude => Self::START_POSITION.1,
+		};
+
+		NEO6M { coordinate, last_read, read_interval: Duration::from_secs(Self::READ_INTERVAL.into()) }
+	}
+}
+
+impl Sensor for NEO6M {
+	fn sample(&mut self, _at: DateTime<Utc>, rng: &mut dyn RngCore) -> f32 {
+		let change: f32 = rng.gen_range(-Self::MAX_STEP..Self::MAX_STEP);
+		let (min, max) = self.value_range();
+
+		self.last_read = (self.last_read + change).clamp(min, max);
+
+		self.last_read
+	}
+
+	fn unit(&self) -> &str {
+		"°"
+	}
+
+	fn name(&self) -> &str {
+		match self.coordinate {
+			Coordinate::Latitude => "NEO6M_LATITUDE",
+			Coordinate::Longitude => "NEO6M_LONGITUDE",
+		}
+	}
+
+	fn value_range(&self) -> (f32, f32) {
+		match self.coordinate {
+			Coordinate::Latitude => Self::LATITUDE_RANGE,
+			Coordinate::Longitude => Self::LONGITUDE_RANGE,
+		}
+	}
+
+	fn read_interval(&self) -> Duration {
+		self.read_interval
+	}
+
+	fn set_read_interval(&mut self, read_interval: Duration) {
+		self.read_interval = read_interval;
+	}
+
+	fn precision(&self) -> usize {
+		6
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::SeedableRng;
+	use rand_chacha::ChaCha8Rng;
+
+	#[test]
+	fn test_neo6m_sensor_data() {
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		let mut sensor = NEO6M::new(Coordinate::Latitude);
+		assert_eq!(sensor.unit(), "°");
+		assert_eq!(sensor.precision(), 6);
+		let read_value = sensor.sample(Utc::now(), &mut rng);
+		assert!(
+			(read_value - NEO6M::START_POSITION.0).abs() < NEO6M::MAX_STEP * 1.1,
+			"Position moved too far in a single read: {}",
+			read_value
+		);
+	}
+}
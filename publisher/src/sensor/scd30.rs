@@ -0,0 +1,67 @@
+use super::{random_walk, Sensor};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use std::time::Duration;
+
+pub struct SCD30 {
+	last_read: f32,
+	read_interval: Duration,
+} // value range: 400ppm - 10000ppm / Read interval: 2s
+
+impl SCD30 {
+	const VALUE_RANGE: (f32, f32) = (400.0, 10000.0);
+	const READ_INTERVAL: u32 = 2;
+
+	pub fn new() -> Self {
+		SCD30 { last_read: 450.0, read_interval: Duration::from_secs(Self::READ_INTERVAL.into()) }
+	}
+}
+
+impl Sensor for SCD30 {
+	fn sample(&mut self, _at: DateTime<Utc>, rng: &mut dyn RngCore) -> f32 {
+		self.last_read = random_walk(rng, self.last_read, 5.0, Self::VALUE_RANGE);
+
+		self.last_read
+	}
+
+	fn unit(&self) -> &str {
+		"ppm"
+	}
+
+	fn name(&self) -> &str {
+		"SCD30"
+	}
+
+	fn value_range(&self) -> (f32, f32) {
+		Self::VALUE_RANGE
+	}
+
+	fn read_interval(&self) -> Duration {
+		self.read_interval
+	}
+
+	fn set_read_interval(&mut self, read_interval: Duration) {
+		self.read_interval = read_interval;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::SeedableRng;
+	use rand_chacha::ChaCha8Rng;
+
+	#[test]
+	fn test_scd30_sensor_data() {
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		let mut sensor = SCD30::new();
+		assert_eq!(sensor.name(), "SCD30");
+		assert_eq!(sensor.unit(), "ppm");
+		let read_value = sensor.sample(Utc::now(), &mut rng);
+		assert!(
+			read_value >= SCD30::VALUE_RANGE.0 && read_value <= SCD30::VALUE_RANGE.1,
+			"Sensor value out of range: {}",
+			read_value
+		);
+	}
+}
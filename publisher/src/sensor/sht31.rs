@@ -0,0 +1,89 @@
+use super::{random_walk, Sensor};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use std::time::Duration;
+
+pub enum Measurement {
+	Temperature,
+	Humidity,
+}
+
+pub struct SHT31 {
+	measurement: Measurement,
+	last_read: f32,
+	read_interval: Duration,
+} // value range: -40°C - 125°C, 0%RH - 100%RH / Read interval: 2s
+
+impl SHT31 {
+	const TEMPERATURE_RANGE: (f32, f32) = (-40.0, 125.0);
+	const HUMIDITY_RANGE: (f32, f32) = (0.0, 100.0);
+	const READ_INTERVAL: u32 = 2;
+
+	pub fn new(measurement: Measurement) -> Self {
+		let last_read = match measurement {
+			Measurement::Temperature => 22.0,
+			Measurement::Humidity => 45.0,
+		};
+
+		SHT31 { measurement, last_read, read_interval: Duration::from_secs(Self::READ_INTERVAL.into()) }
+	}
+}
+
+impl Sensor for SHT31 {
+	fn sample(&mut self, _at: DateTime<Utc>, rng: &mut dyn RngCore) -> f32 {
+		let max_step = match self.measurement {
+			Measurement::Temperature => 0.1,
+			Measurement::Humidity => 0.5,
+		};
+		self.last_read = random_walk(rng, self.last_read, max_step, self.value_range());
+
+		self.last_read
+	}
+
+	fn unit(&self) -> &str {
+		match self.measurement {
+			Measurement::Temperature => "°C",
+			Measurement::Humidity => "%RH",
+		}
+	}
+
+	fn name(&self) -> &str {
+		match self.measurement {
+			Measurement::Temperature => "SHT31_TEMPERATURE",
+			Measurement::Humidity => "SHT31_HUMIDITY",
+		}
+	}
+
+	fn value_range(&self) -> (f32, f32) {
+		match self.measurement {
+			Measurement::Temperature => Self::TEMPERATURE_RANGE,
+			Measurement::Humidity => Self::HUMIDITY_RANGE,
+		}
+	}
+
+	fn read_interval(&self) -> Duration {
+		self.read_interval
+	}
+
+	fn set_read_interval(&mut self, read_interval: Duration) {
+		self.read_interval = read_interval;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::SeedableRng;
+	use rand_chacha::ChaCha8Rng;
+
+	#[test]
+	fn test_sht31_sensor_data() {
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		for measurement in [Measurement::Temperature, Measurement::Humidity] {
+			let mut sensor = SHT31::new(measurement);
+			let (min, max) = sensor.value_range();
+			let read_value = sensor.sample(Utc::now(), &mut rng);
+			assert!(read_value >= min && read_value <= max, "{} value out of range: {}", sensor.name(), read_value);
+		}
+	}
+}
@@ -0,0 +1,102 @@
+use super::{random_walk, Sensor};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use std::time::Duration;
+
+pub struct SPS30 {
+	last_read: f32,
+	read_interval: Duration,
+} // value range: 0.3μm - 1.0μm / Read interval: 1s
+
+impl SPS30 {
+	const VALUE_RANGE: (f32, f32) = (0.3, 1.0);
+	const READ_INTERVAL: u32 = 1;
+
+	pub fn new() -> Self {
+		SPS30 { last_read: 0.65, read_interval: Duration::from_secs(Self::READ_INTERVAL.into()) }
+	}
+}
+
+impl Sensor for SPS30 {
+	fn sample(&mut self, _at: DateTime<Utc>, rng: &mut dyn RngCore) -> f32 {
+		self.last_read = random_walk(rng, self.last_read, 0.05, Self::VALUE_RANGE);
+
+		self.last_read
+	}
+
+	fn unit(&self) -> &str {
+		"μg/m³"
+	}
+
+	fn name(&self) -> &str {
+		"SPS30"
+	}
+
+	fn value_range(&self) -> (f32, f32) {
+		Self::VALUE_RANGE
+	}
+
+	fn read_interval(&self) -> Duration {
+		self.read_interval
+	}
+
+	fn set_read_interval(&mut self, read_interval: Duration) {
+		self.read_interval = read_interval;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::SeedableRng;
+	use rand_chacha::ChaCha8Rng;
+	use std::time::Instant;
+
+	#[test]
+	fn test_sps30_sensor_data() {
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		let mut sensor = SPS30::new();
+		assert_eq!(sensor.name(), "SPS30");
+		assert_eq!(sensor.unit(), "μg/m³");
+		let read_value = sensor.sample(Utc::now(), &mut rng);
+		assert!(
+			read_value >= SPS30::VALUE_RANGE.0 && read_value <= SPS30::VALUE_RANGE.1,
+			"Sensor value out of range: {}",
+			read_value
+		);
+	}
+
+	#[test]
+	fn test_sps30_sensor_timing() {
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		let mut sensor = SPS30::new();
+		assert_eq!(sensor.read_interval(), Duration::from_secs(SPS30::READ_INTERVAL.into()));
+
+		let start = Instant::now();
+		sensor.sample(Utc::now(), &mut rng);
+		let duration = start.elapsed();
+
+		assert!(duration < Duration::from_millis(100), "Sampling should not block, but took {:?}", duration);
+	}
+
+	#[test]
+	fn test_sps30_custom_read_interval() {
+		let mut sensor = SPS30::new();
+		let read_interval = Duration::from_millis(200);
+		sensor.set_read_interval(read_interval);
+
+		assert_eq!(sensor.read_interval(), read_interval);
+	}
+
+	#[test]
+	fn test_sps30_seeded_stream() {
+		let stream = |seed: u64| {
+			let mut rng = ChaCha8Rng::seed_from_u64(seed);
+			let mut sensor = SPS30::new();
+			(0..20).map(|_| sensor.sample(Utc::now(), &mut rng)).collect::<Vec<_>>()
+		};
+
+		assert_eq!(stream(42), stream(42));
+		assert_ne!(stream(42), stream(43));
+	}
+}
@@ -0,0 +1,360 @@
+use crate::sensor::Sensor;
+use chrono::{DateTime, Utc};
+use rand::{Rng, RngCore};
+use rand_distr::{Distribution, StandardNormal};
+use serde::Deserialize;
+use std::{f64::consts::PI, time::Duration};
+
+pub trait Signal: Send {
+	fn value(&mut self, at: DateTime<Utc>, rng: &mut dyn RngCore) -> f64;
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SignalConfig {
+	Constant { value: f64 },
+	Sine { amplitude: f64, period: Option<f64>, phase: Option<f64> },
+	Noise { std_dev: f64 },
+	OrnsteinUhlenbeck { mean: f64, theta: f64, sigma: f64 },
+	RandomWalk { start: f64, step: f64 },
+	Step { after: f64, value: f64 },
+	Drift { rate: f64 },
+	Spike { probability: f64, magnitude: f64 },
+}
+
+impl SignalConfig {
+	const DAY: f64 = 86400.0;
+
+	pub fn validate(&self) -> Result<(), String> {
+		if let Some((name, _)) = self.parameters().into_iter().find(|(_, value)| !value.is_finite()) {
+			return Err(format!("{} must be a finite number", name));
+		}
+
+		let checks = match self {
+			SignalConfig::Sine { period: Some(period), .. } => vec![(*period > 0.0, "period must be positive")],
+			SignalConfig::Noise { std_dev } => vec![(*std_dev >= 0.0, "std_dev must not be negative")],
+			SignalConfig::OrnsteinUhlenbeck { theta, sigma, .. } => {
+				vec![(*theta > 0.0, "theta must be positive"), (*sigma >= 0.0, "sigma must not be negative")]
+			}
+			// The walk draws from -step..step, whose width must stay finite
+			SignalConfig::RandomWalk { step, .. } => {
+				vec![(*step > 0.0, "step must be positive"), (*step <= f64::MAX / 2.0, "step is too large")]
+			}
+			SignalConfig::Step { after, .. } => vec![(*after >= 0.0, "after must not be negative")],
+			SignalConfig::Spike { probability, .. } => {
+				vec![((0.0..=1.0).contains(probability), "probability must be between 0 and 1")]
+			}
+			_ => vec![],
+		};
+
+		match checks.into_iter().find(|(valid, _)| !valid) {
+			Some((_, message)) => Err(message.to_owned()),
+			None => Ok(()),
+		}
+	}
+
+	fn parameters(&self) -> Vec<(&'static str, f64)> {
+		match *self {
+			SignalConfig::Constant { value } => vec![("value", value)],
+			SignalConfig::Sine { amplitude, period, phase } => {
+				let mut parameters = vec![("amplitude", amplitude)];
+				parameters.extend(period.map(|period| ("period", period)));
+				parameters.extend(phase.map(|phase| ("phase", phase)));
+				parameters
+			}
+			SignalConfig::Noise { std_dev } => vec![("std_dev", std_dev)],
+			SignalConfig::OrnsteinUhlenbeck { mean, theta, sigma } => vec![("mean", mean), ("theta", theta), ("sigma", sigma)],
+			SignalConfig::RandomWalk { start, step } => vec![("start", start), ("step", step)],
+			SignalConfig::Step { after, value } => vec![("after", after), ("value", value)],
+			SignalConfig::Drift { rate } => vec![("rate", rate)],
+			SignalConfig::Spike { probability, magnitude } => vec![("probability", probability), ("magnitude", magnitude)],
+		}
+	}
+
+	pub fn build(&self) -> Box<dyn Signal> {
+		match *self {
+			SignalConfig::Constant { value } => Box::new(Constant { value }),
+			SignalConfig::Sine { amplitude, period, phase } => {
+				Box::new(Sine { amplitude, period: period.unwrap_or(Self::DAY), phase: phase.unwrap_or(0.0) })
+			}
+			SignalConfig::Noise { std_dev } => Box::new(Noise { std_dev }),
+			SignalConfig::OrnsteinUhlenbeck { mean, theta, sigma } => {
+				Box::new(OrnsteinUhlenbeck { mean, theta, sigma, state: None })
+			}
+			SignalConfig::RandomWalk { start, step } => Box::new(RandomWalk { value: start, step }),
+			SignalConfig::Step { after, value } => Box::new(Step { after, value, start: None }),
+			SignalConfig::Drift { rate } => Box::new(Drift { rate, start: None }),
+			SignalConfig::Spike { probability, magnitude } => Box::new(Spike { probability, magnitude }),
+		}
+	}
+}
+
+pub struct Constant {
+	value: f64,
+}
+
+impl Signal for Constant {
+	fn value(&mut self, _at: DateTime<Utc>, _rng: &mut dyn RngCore) -> f64 {
+		self.value
+	}
+}
+
+pub struct Sine {
+	amplitude: f64,
+	period: f64,
+	phase: f64,
+}
+
+impl Signal for Sine {
+	fn value(&mut self, at: DateTime<Utc>, _rng: &mut dyn RngCore) -> f64 {
+		let seconds = at.timestamp_millis() as f64 / 1000.0;
+		self.amplitude * (2.0 * PI * (seconds - self.phase) / self.period).sin()
+	}
+}
+
+pub struct Noise {
+	std_dev: f64,
+}
+
+impl Signal for Noise {
+	fn value(&mut self, _at: DateTime<Utc>, rng: &mut dyn RngCore) -> f64 {
+		let z: f64 = StandardNormal.sample(rng);
+		z * self.std_dev
+	}
+}
+
+pub struct OrnsteinUhlenbeck {
+	mean: f64,
+	theta: f64,
+	sigma: f64,
+	state: Option<(DateTime<Utc>, f64)>,
+}
+
+impl Signal for OrnsteinUhlenbeck {
+	fn value(&mut self, at: DateTime<Utc>, rng: &mut dyn RngCore) -> f64 {
+		let value = match self.state {
+			None => self.mean,
+			Some((last_at, last_value)) => {
+				let dt = elapsed_seconds(last_at, at);
+				let decay = (-self.theta * dt).exp();
+				let std_dev = self.sigma * ((1.0 - decay * decay) / (2.0 * self.theta)).sqrt();
+				let z: f64 = StandardNormal.sample(rng);
+
+				self.mean + (last_value - self.mean) * decay + std_dev * z
+			}
+		};
+
+		self.state = Some((at, value));
+		value
+	}
+}
+
+pub struct RandomWalk {
+	value: f64,
+	step: f64,
+}
+
+impl Signal for RandomWalk {
+	fn value(&mut self, _at: DateTime<Utc>, rng: &mut dyn RngCore) -> f64 {
+		self.value += rng.gen_range(-self.step..self.step);
+		self.value
+	}
+}
+
+pub struct Step {
+	after: f64,
+	value: f64,
+	start: Option<DateTime<Utc>>,
+}
+
+impl Signal for Step {
+	fn value(&mut self, at: DateTime<Utc>, _rng: &mut dyn RngCore) -> f64 {
+		let start = *self.start.get_or_insert(at);
+		if elapsed_seconds(start, at) >= self.after {
+			self.value
+		} else {
+			0.0
+		}
+	}
+}
+
+pub struct Drift {
+	rate: f64,
+	start: Option<DateTime<Utc>>,
+}
+
+impl Signal for Drift {
+	fn value(&mut self, at: DateTime<Utc>, _rng: &mut dyn RngCore) -> f64 {
+		let start = *self.start.get_or_insert(at);
+		self.rate * elapsed_seconds(start, at) / 3600.0
+	}
+}
+
+pub struct Spike {
+	probability: f64,
+	magnitude: f64,
+}
+
+impl Signal for Spike {
+	fn value(&mut self, _at: DateTime<Utc>, rng: &mut dyn RngCore) -> f64 {
+		if rng.gen_bool(self.probability) {
+			self.magnitude
+		} else {
+			0.0
+		}
+	}
+}
+
+pub struct ModeledSensor {
+	sensor: Box<dyn Sensor>,
+	signals: Vec<Box<dyn Signal>>,
+}
+
+impl ModeledSensor {
+	pub fn new(sensor: Box<dyn Sensor>, signals: Vec<Box<dyn Signal>>) -> Self {
+		ModeledSensor { sensor, signals }
+	}
+}
+
+impl Sensor for ModeledSensor {
+	fn sample(&mut self, at: DateTime<Utc>, rng: &mut dyn RngCore) -> f32 {
+		let value: f64 = self.signals.iter_mut().map(|signal| signal.value(at, rng)).sum();
+		let (min, max) = self.sensor.value_range();
+
+		(value as f32).clamp(min, max)
+	}
+
+	fn unit(&self) -> &str {
+		self.sensor.unit()
+	}
+
+	fn name(&self) -> &str {
+		self.sensor.name()
+	}
+
+	fn value_range(&self) -> (f32, f32) {
+		self.sensor.value_range()
+	}
+
+	fn read_interval(&self) -> Duration {
+		self.sensor.read_interval()
+	}
+
+	fn set_read_interval(&mut self, read_interval: Duration) {
+		self.sensor.set_read_interval(read_interval);
+	}
+
+	fn precision(&self) -> usize {
+		self.sensor.precision()
+	}
+}
+
+fn elapsed_seconds(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+	(to - from).num_milliseconds().max(0) as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sensor::SHT31;
+	use rand::SeedableRng;
+	use rand_chacha::ChaCha8Rng;
+
+	fn start() -> DateTime<Utc> {
+		DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z").unwrap().with_timezone(&Utc)
+	}
+
+	fn samples(config: &str, count: i64, step: chrono::Duration) -> Vec<f64> {
+		let config: SignalConfig = toml::from_str(config).expect("Failed to parse signal");
+		config.validate().expect("Signal should be valid");
+
+		let mut signal = config.build();
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+		(0..count).map(|i| signal.value(start() + step * i as i32, &mut rng)).collect()
+	}
+
+	#[test]
+	fn test_sine_daily_cycle() {
+		let values = samples("type = \"sine\"\namplitude = 5.0", 5, chrono::Duration::hours(6));
+		let expected = [0.0, 5.0, 0.0, -5.0, 0.0];
+
+		for (value, expected) in values.iter().zip(expected) {
+			assert!((value - expected).abs() < 1e-9, "Expected {}, got {}", expected, value);
+		}
+	}
+
+	#[test]
+	fn test_noise_distribution() {
+		let values = samples("type = \"noise\"\nstd_dev = 2.0", 10000, chrono::Duration::seconds(1));
+		let mean = values.iter().sum::<f64>() / values.len() as f64;
+		let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / values.len() as f64;
+
+		assert!(mean.abs() < 0.1, "Noise mean should be close to 0, got {}", mean);
+		assert!((variance.sqrt() - 2.0).abs() < 0.1, "Noise std_dev should be close to 2, got {}", variance.sqrt());
+	}
+
+	#[test]
+	fn test_ornstein_uhlenbeck_mean_reversion() {
+		let values = samples(
+			"type = \"ornstein_uhlenbeck\"\nmean = 20.0\ntheta = 0.01\nsigma = 0.5",
+			5000,
+			chrono::Duration::seconds(10),
+		);
+		let mean = values.iter().sum::<f64>() / values.len() as f64;
+
+		assert_eq!(values[0], 20.0);
+		assert!((mean - 20.0).abs() < 1.0, "Process should revert to its mean, got {}", mean);
+	}
+
+	#[test]
+	fn test_step_and_drift() {
+		let step = samples("type = \"step\"\nafter = 60\nvalue = 3.0", 4, chrono::Duration::seconds(30));
+		assert_eq!(step, [0.0, 0.0, 3.0, 3.0]);
+
+		let drift = samples("type = \"drift\"\nrate = 1.5", 3, chrono::Duration::hours(2));
+		assert_eq!(drift, [0.0, 3.0, 6.0]);
+	}
+
+	#[test]
+	fn test_spike_probability() {
+		let never = samples("type = \"spike\"\nprobability = 0.0\nmagnitude = 10.0", 100, chrono::Duration::seconds(1));
+		let always = samples("type = \"spike\"\nprobability = 1.0\nmagnitude = 10.0", 100, chrono::Duration::seconds(1));
+
+		assert!(never.iter().all(|value| *value == 0.0));
+		assert!(always.iter().all(|value| *value == 10.0));
+	}
+
+	#[test]
+	fn test_invalid_signals() {
+		let cases = [
+			("type = \"noise\"\nstd_dev = -1.0", "std_dev must not be negative"),
+			("type = \"ornstein_uhlenbeck\"\nmean = 0.0\ntheta = 0.0\nsigma = 1.0", "theta must be positive"),
+			("type = \"spike\"\nprobability = 1.5\nmagnitude = 1.0", "probability must be between 0 and 1"),
+			("type = \"sine\"\namplitude = 1.0\nperiod = 0.0", "period must be positive"),
+			("type = \"random_walk\"\nstart = 0.0\nstep = inf", "step must be a finite number"),
+			("type = \"random_walk\"\nstart = 0.0\nstep = 1e308", "step is too large"),
+			("type = \"random_walk\"\nstart = nan\nstep = 1.0", "start must be a finite number"),
+			("type = \"constant\"\nvalue = inf", "value must be a finite number"),
+			("type = \"sine\"\namplitude = -inf", "amplitude must be a finite number"),
+			("type = \"ornstein_uhlenbeck\"\nmean = nan\ntheta = 1.0\nsigma = 1.0", "mean must be a finite number"),
+			("type = \"drift\"\nrate = inf", "rate must be a finite number"),
+			("type = \"spike\"\nprobability = 0.5\nmagnitude = nan", "magnitude must be a finite number"),
+		];
+
+		for (case, message) in cases {
+			let config: SignalConfig = toml::from_str(case).expect("Failed to parse signal");
+			assert_eq!(config.validate(), Err(message.to_owned()), "Signal should be invalid: {}", case);
+		}
+	}
+
+	#[test]
+	fn test_modeled_sensor() {
+		let signals = vec![SignalConfig::Constant { value: 22.0 }.build(), SignalConfig::Drift { rate: 200.0 }.build()];
+		let mut sensor = ModeledSensor::new(Box::new(SHT31::new(crate::sensor::Measurement::Temperature)), signals);
+		let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+		assert_eq!(sensor.name(), "SHT31_TEMPERATURE");
+		assert_eq!(sensor.sample(start(), &mut rng), 22.0);
+		assert_eq!(sensor.sample(start() + chrono::Duration::hours(1), &mut rng), 125.0, "Value should be clamped to the sensor range");
+	}
+}
@@ -0,0 +1,57 @@
+use common::message::{Availability, StatusMessage};
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct AvailabilityTracker {
+	devices: HashMap<String, Availability>,
+}
+
+impl AvailabilityTracker {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn update(&mut self, message: &StatusMessage) -> bool {
+		let previous = self.devices.insert(message.device_id.clone(), message.status);
+		let changed = previous != Some(message.status);
+
+		if changed {
+			println!("Device {} is {}, {} devices online", message.device_id, message.status, self.online());
+		}
+		changed
+	}
+
+	pub fn check(&self, device_id: &str) {
+		if self.status(device_id) == Some(Availability::Offline) {
+			eprintln!("Received a reading from device {} while it is offline", device_id);
+		}
+	}
+
+	pub fn status(&self, device_id: &str) -> Option<Availability> {
+		self.devices.get(device_id).copied()
+	}
+
+	pub fn online(&self) -> usize {
+		self.devices.values().filter(|status| **status == Availability::Online).count()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_availability_tracking() {
+		let mut tracker = AvailabilityTracker::new();
+
+		assert!(tracker.update(&StatusMessage::new("station-1", Availability::Online)));
+		assert!(tracker.update(&StatusMessage::new("station-2", Availability::Online)));
+		assert!(!tracker.update(&StatusMessage::new("station-1", Availability::Online)), "Retained status should not be a change");
+		assert_eq!(tracker.online(), 2);
+
+		assert!(tracker.update(&StatusMessage::new("station-2", Availability::Offline)));
+		assert_eq!(tracker.status("station-2"), Some(Availability::Offline));
+		assert_eq!(tracker.status("station-3"), None);
+		assert_eq!(tracker.online(), 1);
+	}
+}
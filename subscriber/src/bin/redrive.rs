@@ -0,0 +1,48 @@
+use chrono::Utc;
+use common::kafka::Kafka;
+use common::message::DeadLetter;
+use common::utils::dotenv;
+use std::{error::Error, time::Duration};
+
+const DEFAULT_GROUP_ID: &str = "redrive";
+const DEFAULT_IDLE_TIMEOUT: f64 = 10.0;
+
+// Publishes the payloads of the dead letter topic back to the topic read by the subscriber, until no dead letter is left
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+	let dead_letter_topic = dotenv::get_var("KAFKA_DEAD_LETTER_TOPIC")?;
+	let topic = match dotenv::parse_var::<String>("REDRIVE_TOPIC") {
+		Some(topic) => topic,
+		None => dotenv::get_var("KAFKA_TOPIC")?,
+	};
+	let group_id = dotenv::parse_var::<String>("REDRIVE_GROUP_ID").unwrap_or(DEFAULT_GROUP_ID.to_owned());
+	let idle_timeout = Duration::from_secs_f64(dotenv::parse_var("REDRIVE_IDLE_TIMEOUT").unwrap_or(DEFAULT_IDLE_TIMEOUT));
+
+	let kafka = Kafka::with_group_id(&group_id)?;
+	kafka.subscribe(&dead_letter_topic)?;
+
+	let started_at = Utc::now();
+	let mut redriven = 0;
+	while let Ok(message) = tokio::time::timeout(idle_timeout, kafka.receive()).await {
+		let message = message?;
+
+		match DeadLetter::decode(&String::from_utf8_lossy(&message.payload)) {
+			// Messages failing again are left uncommitted for the next run, instead of being re-driven in a loop
+			Ok(letter) if letter.failed_at >= started_at => break,
+			Ok(letter) => match letter.payload_bytes() {
+				Some(payload) => {
+					kafka.produce_with_key(&topic, Some("sensors"), &payload).await?;
+					redriven += 1;
+					println!("Re-drove a message that failed with \"{}\" to {}", letter.error, topic);
+				}
+				None => eprintln!("Skipping a dead letter with a corrupted payload at offset {}", message.offset),
+			},
+			Err(e) => eprintln!("Skipping an invalid dead letter at offset {}: {}", message.offset, e),
+		}
+
+		kafka.commit(&message)?;
+	}
+
+	println!("Re-drove {} messages from {} to {}", redriven, dead_letter_topic, topic);
+	Ok(())
+}
@@ -1,40 +1,70 @@
-use serde::{Deserialize, Deserializer};
-
 #[allow(warnings, unused)]
 use crate::models::*;
+use crate::pipeline::MessageSink;
+use common::message::SensorMessage;
 use std::error::Error;
+use std::sync::Arc;
 
-fn parse_float_from_string<'de, D>(deserializer: D) -> Result<f64, D::Error>
-where
-	D: Deserializer<'de>,
-{
-	let s: String = Deserialize::deserialize(deserializer)?;
-	s.parse::<f64>().map_err(serde::de::Error::custom)
-}
-
-#[derive(Deserialize)]
-pub struct SensorData {
-	sensor: String,
-	#[serde(deserialize_with = "parse_float_from_string")]
-	value: f64,
-	unit: String,
-}
-
+// Clones share the client, so the workers use the same connection pool
+#[derive(Clone)]
 pub struct Database {
-	client: PrismaClient,
+	client: Arc<PrismaClient>,
 }
 
 impl Database {
 	pub async fn new() -> Result<Self, Box<dyn Error>> {
 		let client = PrismaClient::_builder().build().await.unwrap();
 
-		Ok(Self { client })
+		Ok(Self { client: Arc::new(client) })
 	}
 
-	pub async fn add_sensor_reading(&self, data: SensorData) -> Result<(), Box<dyn Error>> {
-		self.client.sensor_reading().create(data.sensor, data.unit, data.value, vec![]).exec().await.unwrap();
+	pub async fn add_sensor_reading(&self, message: SensorMessage) -> Result<(), Box<dyn Error>> {
+		let (name, unit, value, params) = reading_data(message)?;
+
+		self.client.sensor_reading().create(name, unit, value, params).exec().await?;
 		Ok(())
 	}
+
+	pub async fn add_sensor_readings(&self, messages: Vec<SensorMessage>) -> Result<i64, Box<dyn Error>> {
+		let readings = messages.into_iter().map(reading_data).collect::<Result<Vec<_>, _>>()?;
+
+		Ok(self.client.sensor_reading().create_many(readings).exec().await?)
+	}
+}
+
+// Sequences are stored as signed integers
+fn check_reading(message: &SensorMessage) -> Result<(), String> {
+	match message.sequence {
+		Some(sequence) if i64::try_from(sequence).is_err() => Err(format!("sequence {} is too large to be stored", sequence)),
+		_ => Ok(()),
+	}
+}
+
+fn reading_data(message: SensorMessage) -> Result<(String, String, f64, Vec<sensor_reading::SetParam>), Box<dyn Error>> {
+	let mut params = vec![sensor_reading::device_id::set(message.device_id)];
+	if let Some(timestamp) = message.timestamp {
+		params.push(sensor_reading::measured_at::set(timestamp.fixed_offset()));
+	}
+	if let Some(sequence) = message.sequence {
+		params.push(sensor_reading::sequence::set(i64::try_from(sequence)?));
+	}
+
+	Ok(sensor_reading::create(message.sensor, message.unit, message.value, params))
+}
+
+impl MessageSink for Database {
+	async fn store(&mut self, message: SensorMessage) -> Result<(), Box<dyn Error>> {
+		self.add_sensor_reading(message).await
+	}
+
+	async fn store_many(&mut self, messages: Vec<SensorMessage>) -> Result<(), Box<dyn Error>> {
+		self.add_sensor_readings(messages).await?;
+		Ok(())
+	}
+
+	fn validate(&self, message: &SensorMessage) -> Result<(), String> {
+		check_reading(message)
+	}
 }
 
 #[cfg(test)]
@@ -46,7 +76,7 @@ mod tests {
 		let db = Database::new().await.expect("Failed to create database client");
 		let test_message = r#"{"sensor":"SPS30","value":42.0,"unit":"μg/m³"}"#;
 
-		db.add_sensor_reading(serde_json::from_str(test_message).unwrap()).await.expect("Failed to add sensor reading");
+		db.add_sensor_reading(SensorMessage::decode(test_message).unwrap()).await.expect("Failed to add sensor reading");
 
 		let sensor_readings = db.client.sensor_reading().find_many(vec![]).exec().await.expect("Failed to fetch sensor readings");
 
@@ -55,4 +85,34 @@ mod tests {
 		assert_eq!(sensor_readings[0].value, 42.0);
 		assert_eq!(sensor_readings[0].unit, "μg/m³");
 	}
+
+	#[test]
+	fn test_check_reading() {
+		let message = |sequence: u64| {
+			SensorMessage::decode(&format!(r#"{{"sensor":"SPS30","value":1,"unit":"μg/m³","sequence":{}}}"#, sequence))
+		};
+
+		assert!(check_reading(&message(7).unwrap()).is_ok());
+		assert_eq!(check_reading(&message(u64::MAX).unwrap()), Err(format!("sequence {} is too large to be stored", u64::MAX)));
+	}
+
+	#[tokio::test]
+	async fn test_reading_metadata() {
+		let db = Database::new().await.expect("Failed to create database client");
+		let test_message = r#"{"version":1,"device_id":"station-test","sensor":"SPS30","value":0.5,"unit":"μg/m³","timestamp":"2024-01-01T12:00:00Z","sequence":7}"#;
+
+		db.add_sensor_reading(SensorMessage::decode(test_message).unwrap()).await.expect("Failed to add sensor reading");
+
+		let sensor_reading = db
+			.client
+			.sensor_reading()
+			.find_first(vec![sensor_reading::device_id::equals("station-test".to_owned())])
+			.exec()
+			.await
+			.expect("Failed to fetch sensor reading")
+			.expect("Sensor reading should exist");
+
+		assert_eq!(sensor_reading.measured_at.to_rfc3339(), "2024-01-01T12:00:00+00:00");
+		assert_eq!(sensor_reading.sequence, 7);
+	}
 }
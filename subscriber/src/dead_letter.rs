@@ -0,0 +1,25 @@
+use crate::pipeline::DeadLetterSink;
+use common::kafka::Kafka;
+use common::message::DeadLetter;
+use std::error::Error;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct DeadLetterTopic {
+	kafka: Arc<Kafka>,
+	topic: String,
+}
+
+impl DeadLetterTopic {
+	pub fn new(topic: &str) -> Result<Self, Box<dyn Error>> {
+		Ok(DeadLetterTopic { kafka: Arc::new(Kafka::producer()?), topic: topic.to_owned() })
+	}
+}
+
+impl DeadLetterSink for DeadLetterTopic {
+	async fn send(&mut self, letter: DeadLetter) -> Result<(), Box<dyn Error>> {
+		self.kafka.produce(&self.topic, &letter.encode()).await?;
+		println!("Message sent to the dead letter topic {}: {}", self.topic, letter.error);
+		Ok(())
+	}
+}
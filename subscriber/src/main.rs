@@ -1,42 +1,121 @@
+mod availability;
 mod database;
-mod kafka;
+mod dead_letter;
+mod mqtt;
+mod partition;
+mod pipeline;
+mod sequence;
+mod source;
+mod throughput;
 
 #[allow(warnings, unused)]
 pub mod models;
 
-use crate::database::{Database, SensorData};
-use crate::kafka::Kafka;
+use crate::availability::AvailabilityTracker;
+use crate::database::Database;
+use crate::dead_letter::DeadLetterTopic;
+use crate::mqtt::Mqtt;
+use crate::partition::{OffsetTracker, OrderBy};
+use crate::pipeline::Pipeline;
+use crate::sequence::SequenceTracker;
+use crate::source::{ReplaySource, WorkerSource};
+use common::kafka::Kafka;
 use common::utils::dotenv;
 use std::error::Error;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+
+const DEFAULT_SOURCE: &str = "kafka";
+const DEFAULT_MQTT_TOPICS: &str = "sensors,devices/+/status";
+const DEFAULT_REPLAY_FILE: &str = "-";
+const DEFAULT_WORKERS: usize = 4;
+const WORKER_QUEUE_SIZE: usize = 100;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-	let db = Arc::new(Database::new().await?);
-	let kafka: Kafka = Kafka::new()?;
+	// Messages that cannot be decoded or stored are published to the dead letter topic, when there is one
+	let dead_letters =
+		dotenv::parse_var::<String>("KAFKA_DEAD_LETTER_TOPIC").map(|topic| DeadLetterTopic::new(&topic)).transpose()?;
+	let database = Database::new().await?;
+
+	// Small deployments can read the messages straight from the broker, without a Kafka cluster
+	match dotenv::parse_var::<String>("SUBSCRIBER_SOURCE").as_deref().unwrap_or(DEFAULT_SOURCE) {
+		"kafka" => consume_kafka(database, dead_letters).await?,
+		"mqtt" => {
+			let mut mqtt = Mqtt::new()?;
+			let topics = dotenv::parse_var::<String>("SUBSCRIBER_MQTT_TOPICS").unwrap_or(DEFAULT_MQTT_TOPICS.to_owned());
+
+			mqtt.subscribe(&topics)?;
+			Pipeline::new(database, dead_letters).run_until(&mut mqtt, shutdown()).await?;
+		}
+		"file" => {
+			let path = dotenv::parse_var::<String>("SUBSCRIBER_REPLAY_FILE").unwrap_or(DEFAULT_REPLAY_FILE.to_owned());
+
+			Pipeline::new(database, dead_letters).run(&mut ReplaySource::open(&path)?).await?;
+			println!("Finished replaying {}", path);
+		}
+		source => return Err(format!("Unknown SUBSCRIBER_SOURCE {}, expected kafka, mqtt or file", source).into()),
+	}
+
+	Ok(())
+}
+
+// Each worker runs its own pipeline, so the partitions are stored in parallel while the messages of each stay in order
+async fn consume_kafka(database: Database, dead_letters: Option<DeadLetterTopic>) -> Result<(), Box<dyn Error>> {
+	let kafka = Arc::new(Kafka::new()?);
 	let topic = dotenv::get_var("KAFKA_TOPIC").unwrap();
+	let workers = dotenv::parse_var("SUBSCRIBER_WORKERS").unwrap_or(DEFAULT_WORKERS).max(1);
+	let order_by: OrderBy = dotenv::parse_var("SUBSCRIBER_ORDER_BY").unwrap_or_default();
+	let offsets = Arc::new(Mutex::new(OffsetTracker::new()));
+	let tracker = Arc::new(Mutex::new(SequenceTracker::new()));
+	let availability = Arc::new(Mutex::new(AvailabilityTracker::new()));
 
 	kafka.subscribe(&topic)?;
 
-	kafka
-		.consume(move |payload| {
-			let db: Arc<Database> = Arc::clone(&db);
-			let payload = payload.to_owned();
-			async move {
-				match serde_json::from_str::<SensorData>(&payload) {
-					Ok(sensor_data) => {
-						if let Err(e) = db.add_sensor_reading(sensor_data).await {
-							eprintln!("Failed to add sensor reading to the database: {}", e);
-						} else {
-							println!("Sensor reading added to the database: {}", payload);
-						}
-					}
-					Err(e) => eprintln!("Failed to deserialize message: {}", e),
-				}
-				Ok(())
-			}
-		})
-		.await?;
+	let mut senders = Vec::new();
+	let mut handles = JoinSet::new();
+	for _ in 0..workers {
+		let (sender, receiver) = mpsc::channel(WORKER_QUEUE_SIZE);
+		let mut source = WorkerSource::new(receiver, kafka.clone(), offsets.clone());
+		let mut pipeline =
+			Pipeline::new(database.clone(), dead_letters.clone()).with_trackers(tracker.clone(), availability.clone());
 
-	Ok(())
+		senders.push(sender);
+		handles.spawn(async move { pipeline.run(&mut source).await.map_err(|e| e.to_string()) });
+	}
+
+	// Workers only stop once their queue is closed, so a worker stopping before stops the consumer right away, instead
+	// of once a message is dispatched to it while the other partitions keep being committed
+	let dispatched: Result<(), Box<dyn Error>> = tokio::select! {
+		dispatched = partition::dispatch(|| kafka.receive(), order_by, &senders, &offsets, shutdown()) => dispatched,
+		Some(stopped) = handles.join_next() => Err(match stopped {
+			Ok(Err(e)) => e.into(),
+			_ => "A worker stopped unexpectedly".into(),
+		}),
+	};
+	// Closing the queues lets the workers store and commit the messages they already received before stopping
+	drop(senders);
+	while let Some(stopped) = handles.join_next().await {
+		stopped??;
+	}
+
+	dispatched
+}
+
+// Docker stops the container with SIGTERM, so the buffered readings are also stored then
+async fn shutdown() {
+	#[cfg(unix)]
+	{
+		use tokio::signal::unix::{signal, SignalKind};
+
+		let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+		tokio::select! {
+			_ = tokio::signal::ctrl_c() => {}
+			_ = terminate.recv() => {}
+		}
+	}
+
+	#[cfg(not(unix))]
+	let _ = tokio::signal::ctrl_c().await;
 }
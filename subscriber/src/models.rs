@@ -321,6 +321,193 @@ pub mod sensor_reading {
 			}
 		}
 	}
+	pub mod device_id {
+		use super::super::*;
+		use super::_prisma::*;
+		use super::{OrderByParam, SetParam, UncheckedSetParam, UniqueWhereParam, WhereParam, WithParam};
+		pub const NAME: &str = "deviceId";
+		pub struct Set(pub String);
+		impl From<Set> for SetParam {
+			fn from(Set(v): Set) -> Self {
+				Self::SetDeviceId(v)
+			}
+		}
+		impl From<Set> for UncheckedSetParam {
+			fn from(Set(v): Set) -> Self {
+				Self::DeviceId(v)
+			}
+		}
+		pub fn set<T: From<Set>>(value: String) -> T {
+			Set(value).into()
+		}
+		pub fn order(direction: ::prisma_client_rust::Direction) -> OrderByParam {
+			OrderByParam::DeviceId(direction)
+		}
+		pub fn equals(value: String) -> WhereParam {
+			WhereParam::DeviceId(_prisma::read_filters::StringFilter::Equals(value))
+		}
+		::prisma_client_rust::scalar_where_param_fns!(_prisma::read_filters::StringFilter, DeviceId, {
+			fn in_vec(_: Vec<String>) -> InVec;
+			fn not_in_vec(_: Vec<String>) -> NotInVec;
+			fn lt(_: String) -> Lt;
+			fn lte(_: String) -> Lte;
+			fn gt(_: String) -> Gt;
+			fn gte(_: String) -> Gte;
+			fn contains(_: String) -> Contains;
+			fn starts_with(_: String) -> StartsWith;
+			fn ends_with(_: String) -> EndsWith;
+			fn mode(_: super::super::QueryMode) -> Mode;
+			fn not(_: String) -> Not;
+		});
+		pub struct Include;
+		impl Into<super::IncludeParam> for Include {
+			fn into(self) -> super::IncludeParam {
+				super::IncludeParam::DeviceId(self)
+			}
+		}
+		impl Include {
+			pub fn to_selection(self) -> ::prisma_client_rust::Selection {
+				::prisma_client_rust::sel(NAME)
+			}
+		}
+		pub struct Select;
+		impl Into<super::SelectParam> for Select {
+			fn into(self) -> super::SelectParam {
+				super::SelectParam::DeviceId(self)
+			}
+		}
+		impl Select {
+			pub fn to_selection(self) -> ::prisma_client_rust::Selection {
+				::prisma_client_rust::sel(NAME)
+			}
+		}
+	}
+	pub mod measured_at {
+		use super::super::*;
+		use super::_prisma::*;
+		use super::{OrderByParam, SetParam, UncheckedSetParam, UniqueWhereParam, WhereParam, WithParam};
+		pub const NAME: &str = "measuredAt";
+		pub struct Set(pub ::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>);
+		impl From<Set> for SetParam {
+			fn from(Set(v): Set) -> Self {
+				Self::SetMeasuredAt(v)
+			}
+		}
+		impl From<Set> for UncheckedSetParam {
+			fn from(Set(v): Set) -> Self {
+				Self::MeasuredAt(v)
+			}
+		}
+		pub fn set<T: From<Set>>(value: ::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>) -> T {
+			Set(value).into()
+		}
+		pub fn order(direction: ::prisma_client_rust::Direction) -> OrderByParam {
+			OrderByParam::MeasuredAt(direction)
+		}
+		pub fn equals(value: ::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>) -> WhereParam {
+			WhereParam::MeasuredAt(_prisma::read_filters::DateTimeFilter::Equals(value))
+		}
+		::prisma_client_rust::scalar_where_param_fns!(_prisma::read_filters::DateTimeFilter, MeasuredAt, {
+			fn in_vec(_: Vec<::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>>) -> InVec;
+			fn not_in_vec(_: Vec<::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>>) -> NotInVec;
+			fn lt(_: ::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>) -> Lt;
+			fn lte(_: ::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>) -> Lte;
+			fn gt(_: ::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>) -> Gt;
+			fn gte(_: ::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>) -> Gte;
+			fn not(_: ::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>) -> Not;
+		});
+		pub struct Include;
+		impl Into<super::IncludeParam> for Include {
+			fn into(self) -> super::IncludeParam {
+				super::IncludeParam::MeasuredAt(self)
+			}
+		}
+		impl Include {
+			pub fn to_selection(self) -> ::prisma_client_rust::Selection {
+				::prisma_client_rust::sel(NAME)
+			}
+		}
+		pub struct Select;
+		impl Into<super::SelectParam> for Select {
+			fn into(self) -> super::SelectParam {
+				super::SelectParam::MeasuredAt(self)
+			}
+		}
+		impl Select {
+			pub fn to_selection(self) -> ::prisma_client_rust::Selection {
+				::prisma_client_rust::sel(NAME)
+			}
+		}
+	}
+	pub mod sequence {
+		use super::super::*;
+		use super::_prisma::*;
+		use super::{OrderByParam, SetParam, UncheckedSetParam, UniqueWhereParam, WhereParam, WithParam};
+		pub const NAME: &str = "sequence";
+		pub struct Set(pub i64);
+		impl From<Set> for SetParam {
+			fn from(Set(v): Set) -> Self {
+				Self::SetSequence(v)
+			}
+		}
+		impl From<Set> for UncheckedSetParam {
+			fn from(Set(v): Set) -> Self {
+				Self::Sequence(v)
+			}
+		}
+		pub fn set<T: From<Set>>(value: i64) -> T {
+			Set(value).into()
+		}
+		pub fn order(direction: ::prisma_client_rust::Direction) -> OrderByParam {
+			OrderByParam::Sequence(direction)
+		}
+		pub fn equals(value: i64) -> WhereParam {
+			WhereParam::Sequence(_prisma::read_filters::BigIntFilter::Equals(value))
+		}
+		::prisma_client_rust::scalar_where_param_fns!(_prisma::read_filters::BigIntFilter, Sequence, {
+			fn in_vec(_: Vec<i64>) -> InVec;
+			fn not_in_vec(_: Vec<i64>) -> NotInVec;
+			fn lt(_: i64) -> Lt;
+			fn lte(_: i64) -> Lte;
+			fn gt(_: i64) -> Gt;
+			fn gte(_: i64) -> Gte;
+			fn not(_: i64) -> Not;
+		});
+		pub fn increment(value: i64) -> SetParam {
+			SetParam::IncrementSequence(value)
+		}
+		pub fn decrement(value: i64) -> SetParam {
+			SetParam::DecrementSequence(value)
+		}
+		pub fn multiply(value: i64) -> SetParam {
+			SetParam::MultiplySequence(value)
+		}
+		pub fn divide(value: i64) -> SetParam {
+			SetParam::DivideSequence(value)
+		}
+		pub struct Include;
+		impl Into<super::IncludeParam> for Include {
+			fn into(self) -> super::IncludeParam {
+				super::IncludeParam::Sequence(self)
+			}
+		}
+		impl Include {
+			pub fn to_selection(self) -> ::prisma_client_rust::Selection {
+				::prisma_client_rust::sel(NAME)
+			}
+		}
+		pub struct Select;
+		impl Into<super::SelectParam> for Select {
+			fn into(self) -> super::SelectParam {
+				super::SelectParam::Sequence(self)
+			}
+		}
+		impl Select {
+			pub fn to_selection(self) -> ::prisma_client_rust::Selection {
+				::prisma_client_rust::sel(NAME)
+			}
+		}
+	}
 	pub fn create(name: String, unit: String, value: f64, _params: Vec<SetParam>) -> (String, String, f64, Vec<SetParam>) {
 		(name, unit, value, _params)
 	}
@@ -333,7 +520,7 @@ pub mod sensor_reading {
 		(name, unit, value, _params)
 	}
 	#[macro_export]
-	macro_rules ! _select_sensor_reading { ($ (($ ($ func_arg : ident : $ func_arg_ty : ty) , +) =>) ? $ module_name : ident { $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { # [allow (warnings)] pub mod $ module_name { crate :: prisma :: sensor_reading :: select ! (@ definitions ; $ module_name ; $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) +) ; use super :: * ; pub struct Selection (Vec < :: prisma_client_rust :: Selection >) ; impl :: prisma_client_rust :: SelectType for Selection { type Data = Data ; type ModelData = crate :: prisma :: sensor_reading :: Data ; fn to_selections (self) -> Vec < :: prisma_client_rust :: Selection > { self . 0 } } pub fn select ($ ($ ($ func_arg : $ func_arg_ty) , +) ?) -> Selection { Selection ([crate :: prisma :: sensor_reading :: select ! (@ selections_to_params ; : select { $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) + }) . into_iter () . map (| p | p . to_selection ()) . collect :: < Vec < _ >> () ,] . into_iter () . flatten () . collect :: < Vec < _ >> ()) } } } ; ({ $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { { crate :: prisma :: sensor_reading :: select ! (@ definitions ; ; $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) +) ; pub struct Selection (Vec < :: prisma_client_rust :: Selection >) ; impl :: prisma_client_rust :: SelectType for Selection { type Data = Data ; type ModelData = crate :: prisma :: sensor_reading :: Data ; fn to_selections (self) -> Vec < :: prisma_client_rust :: Selection > { self . 0 } } Selection ([crate :: prisma :: sensor_reading :: select ! (@ selections_to_params ; : select { $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) + }) . into_iter () . map (| p | p . to_selection ()) . collect :: < Vec < _ >> () ,] . into_iter () . flatten () . collect :: < Vec < _ >> ()) } } ; (@ definitions ; $ ($ module_name : ident) ? ; $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) +) => { # [allow (warnings)] enum Fields { id , created_at , name , unit , value } # [allow (warnings)] impl Fields { fn selections () { $ (let _ = Fields :: $ field ;) + } } # [allow (warnings)] # [derive (std :: fmt :: Debug , Clone)] pub struct Data { $ (pub $ field : crate :: prisma :: sensor_reading :: select ! (@ field_type ; $ field $ (: $ selection_mode { $ ($ selections) + }) ?) ,) + } impl :: serde :: Serialize for Data { fn serialize < S > (& self , serializer : S) -> Result < S :: Ok , S :: Error > where S : :: serde :: Serializer , { use :: serde :: ser :: SerializeStruct ; let mut state = serializer . serialize_struct ("Data" , [$ (stringify ! ($ field) ,) +] . len ()) ? ; $ (state . serialize_field (crate :: prisma :: sensor_reading :: $ field :: NAME , & self . $ field) ? ;) * state . end () } } impl < 'de > :: serde :: Deserialize < 'de > for Data { fn deserialize < D > (deserializer : D) -> Result < Self , D :: Error > where D : :: serde :: Deserializer < 'de > , { # [allow (warnings)] enum Field { $ ($ field) , + , } impl < 'de > :: serde :: Deserialize < 'de > for Field { fn deserialize < D > (deserializer : D) -> Result < Field , D :: Error > where D : :: serde :: Deserializer < 'de > , { struct FieldVisitor ; impl < 'de > :: serde :: de :: Visitor < 'de > for FieldVisitor { type Value = Field ; fn expecting (& self , formatter : & mut :: std :: fmt :: Formatter) -> :: std :: fmt :: Result { formatter . write_str (& [$ (crate :: prisma :: sensor_reading :: $ field :: NAME) , + ,] . into_iter () . collect :: < Vec < _ >> () . join (", ")) } fn visit_str < E > (self , value : & str) -> Result < Field , E > where E : :: serde :: de :: Error , { match value { $ (crate :: prisma :: sensor_reading :: $ field :: NAME => Ok (Field :: $ field)) , * , _ => Err (:: serde :: de :: Error :: unknown_field (value , FIELDS)) , } } } deserializer . deserialize_identifier (FieldVisitor) } } struct DataVisitor ; impl < 'de > :: serde :: de :: Visitor < 'de > for DataVisitor { type Value = Data ; fn expecting (& self , formatter : & mut std :: fmt :: Formatter) -> std :: fmt :: Result { formatter . write_str ("struct Data") } fn visit_map < V > (self , mut map : V) -> Result < Data , V :: Error > where V : :: serde :: de :: MapAccess < 'de > , { $ (let mut $ field = None ;) * while let Some (key) = map . next_key () ? { match key { $ (Field :: $ field => { if $ field . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: $ field :: NAME)) ; } $ field = Some (map . next_value () ?) ; }) * } } $ (let $ field = $ field . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: $ field :: NAME)) ? ;) * Ok (Data { $ ($ field) , * }) } } const FIELDS : & 'static [& 'static str] = & ["id" , "createdAt" , "name" , "unit" , "value"] ; deserializer . deserialize_struct ("Data" , FIELDS , DataVisitor) } } $ ($ (pub mod $ field { crate :: prisma :: sensor_reading :: $ selection_mode ! (@ field_module ; $ field : $ selection_mode { $ ($ selections) + }) ; }) ?) + } ; (@ field_type ; id) => { String } ; (@ field_type ; created_at) => { :: prisma_client_rust :: chrono :: DateTime < :: prisma_client_rust :: chrono :: FixedOffset , > } ; (@ field_type ; name) => { String } ; (@ field_type ; unit) => { String } ; (@ field_type ; value) => { f64 } ; (@ field_type ; $ field : ident $ ($ tokens : tt) *) => { compile_error ! (stringify ! (Cannot include nonexistent relation $ field on model "SensorReading" , available relations are "id, created_at, name, unit, value")) } ; (@ field_module ; $ ($ tokens : tt) *) => { } ; (@ selection_field_to_selection_param ; id) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: id :: Select) } ; (@ selection_field_to_selection_param ; created_at) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: created_at :: Select) } ; (@ selection_field_to_selection_param ; name) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: name :: Select) } ; (@ selection_field_to_selection_param ; unit) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: unit :: Select) } ; (@ selection_field_to_selection_param ; value) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: value :: Select) } ; (@ selection_field_to_selection_param ; $ ($ tokens : tt) *) => { compile_error ! (stringify ! ($ ($ tokens) *)) } ; (@ selections_to_params ; : $ macro_name : ident { $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { [$ (crate :: prisma :: sensor_reading :: $ macro_name ! (@ selection_field_to_selection_param ; $ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) ,) +] } ; (@ filters_to_args ;) => { vec ! [] } ; (@ filters_to_args ; $ ($ t : tt) *) => { $ ($ t) * } ; (@ field_serde_name ; id) => { "id" } ; (@ field_serde_name ; created_at) => { "createdAt" } ; (@ field_serde_name ; name) => { "name" } ; (@ field_serde_name ; unit) => { "unit" } ; (@ field_serde_name ; value) => { "value" } ; }
+	macro_rules ! _select_sensor_reading { ($ (($ ($ func_arg : ident : $ func_arg_ty : ty) , +) =>) ? $ module_name : ident { $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { # [allow (warnings)] pub mod $ module_name { crate :: prisma :: sensor_reading :: select ! (@ definitions ; $ module_name ; $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) +) ; use super :: * ; pub struct Selection (Vec < :: prisma_client_rust :: Selection >) ; impl :: prisma_client_rust :: SelectType for Selection { type Data = Data ; type ModelData = crate :: prisma :: sensor_reading :: Data ; fn to_selections (self) -> Vec < :: prisma_client_rust :: Selection > { self . 0 } } pub fn select ($ ($ ($ func_arg : $ func_arg_ty) , +) ?) -> Selection { Selection ([crate :: prisma :: sensor_reading :: select ! (@ selections_to_params ; : select { $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) + }) . into_iter () . map (| p | p . to_selection ()) . collect :: < Vec < _ >> () ,] . into_iter () . flatten () . collect :: < Vec < _ >> ()) } } } ; ({ $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { { crate :: prisma :: sensor_reading :: select ! (@ definitions ; ; $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) +) ; pub struct Selection (Vec < :: prisma_client_rust :: Selection >) ; impl :: prisma_client_rust :: SelectType for Selection { type Data = Data ; type ModelData = crate :: prisma :: sensor_reading :: Data ; fn to_selections (self) -> Vec < :: prisma_client_rust :: Selection > { self . 0 } } Selection ([crate :: prisma :: sensor_reading :: select ! (@ selections_to_params ; : select { $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) + }) . into_iter () . map (| p | p . to_selection ()) . collect :: < Vec < _ >> () ,] . into_iter () . flatten () . collect :: < Vec < _ >> ()) } } ; (@ definitions ; $ ($ module_name : ident) ? ; $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) +) => { # [allow (warnings)] enum Fields { id , created_at , name , unit , value , device_id , measured_at , sequence } # [allow (warnings)] impl Fields { fn selections () { $ (let _ = Fields :: $ field ;) + } } # [allow (warnings)] # [derive (std :: fmt :: Debug , Clone)] pub struct Data { $ (pub $ field : crate :: prisma :: sensor_reading :: select ! (@ field_type ; $ field $ (: $ selection_mode { $ ($ selections) + }) ?) ,) + } impl :: serde :: Serialize for Data { fn serialize < S > (& self , serializer : S) -> Result < S :: Ok , S :: Error > where S : :: serde :: Serializer , { use :: serde :: ser :: SerializeStruct ; let mut state = serializer . serialize_struct ("Data" , [$ (stringify ! ($ field) ,) +] . len ()) ? ; $ (state . serialize_field (crate :: prisma :: sensor_reading :: $ field :: NAME , & self . $ field) ? ;) * state . end () } } impl < 'de > :: serde :: Deserialize < 'de > for Data { fn deserialize < D > (deserializer : D) -> Result < Self , D :: Error > where D : :: serde :: Deserializer < 'de > , { # [allow (warnings)] enum Field { $ ($ field) , + , } impl < 'de > :: serde :: Deserialize < 'de > for Field { fn deserialize < D > (deserializer : D) -> Result < Field , D :: Error > where D : :: serde :: Deserializer < 'de > , { struct FieldVisitor ; impl < 'de > :: serde :: de :: Visitor < 'de > for FieldVisitor { type Value = Field ; fn expecting (& self , formatter : & mut :: std :: fmt :: Formatter) -> :: std :: fmt :: Result { formatter . write_str (& [$ (crate :: prisma :: sensor_reading :: $ field :: NAME) , + ,] . into_iter () . collect :: < Vec < _ >> () . join (", ")) } fn visit_str < E > (self , value : & str) -> Result < Field , E > where E : :: serde :: de :: Error , { match value { $ (crate :: prisma :: sensor_reading :: $ field :: NAME => Ok (Field :: $ field)) , * , _ => Err (:: serde :: de :: Error :: unknown_field (value , FIELDS)) , } } } deserializer . deserialize_identifier (FieldVisitor) } } struct DataVisitor ; impl < 'de > :: serde :: de :: Visitor < 'de > for DataVisitor { type Value = Data ; fn expecting (& self , formatter : & mut std :: fmt :: Formatter) -> std :: fmt :: Result { formatter . write_str ("struct Data") } fn visit_map < V > (self , mut map : V) -> Result < Data , V :: Error > where V : :: serde :: de :: MapAccess < 'de > , { $ (let mut $ field = None ;) * while let Some (key) = map . next_key () ? { match key { $ (Field :: $ field => { if $ field . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: $ field :: NAME)) ; } $ field = Some (map . next_value () ?) ; }) * } } $ (let $ field = $ field . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: $ field :: NAME)) ? ;) * Ok (Data { $ ($ field) , * }) } } const FIELDS : & 'static [& 'static str] = & ["id" , "createdAt" , "name" , "unit" , "value" , "deviceId" , "measuredAt" , "sequence"] ; deserializer . deserialize_struct ("Data" , FIELDS , DataVisitor) } } $ ($ (pub mod $ field { crate :: prisma :: sensor_reading :: $ selection_mode ! (@ field_module ; $ field : $ selection_mode { $ ($ selections) + }) ; }) ?) + } ; (@ field_type ; id) => { String } ; (@ field_type ; created_at) => { :: prisma_client_rust :: chrono :: DateTime < :: prisma_client_rust :: chrono :: FixedOffset , > } ; (@ field_type ; name) => { String } ; (@ field_type ; unit) => { String } ; (@ field_type ; value) => { f64 } ; (@ field_type ; device_id) => { String } ; (@ field_type ; measured_at) => { :: prisma_client_rust :: chrono :: DateTime < :: prisma_client_rust :: chrono :: FixedOffset , > } ; (@ field_type ; sequence) => { i64 } ; (@ field_type ; $ field : ident $ ($ tokens : tt) *) => { compile_error ! (stringify ! (Cannot include nonexistent relation $ field on model "SensorReading" , available relations are "id, created_at, name, unit, value, device_id, measured_at, sequence")) } ; (@ field_module ; $ ($ tokens : tt) *) => { } ; (@ selection_field_to_selection_param ; id) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: id :: Select) } ; (@ selection_field_to_selection_param ; created_at) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: created_at :: Select) } ; (@ selection_field_to_selection_param ; name) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: name :: Select) } ; (@ selection_field_to_selection_param ; unit) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: unit :: Select) } ; (@ selection_field_to_selection_param ; value) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: value :: Select) } ; (@ selection_field_to_selection_param ; device_id) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: device_id :: Select) } ; (@ selection_field_to_selection_param ; measured_at) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: measured_at :: Select) } ; (@ selection_field_to_selection_param ; sequence) => { Into :: < crate :: prisma :: sensor_reading :: SelectParam > :: into (crate :: prisma :: sensor_reading :: sequence :: Select) } ; (@ selection_field_to_selection_param ; $ ($ tokens : tt) *) => { compile_error ! (stringify ! ($ ($ tokens) *)) } ; (@ selections_to_params ; : $ macro_name : ident { $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { [$ (crate :: prisma :: sensor_reading :: $ macro_name ! (@ selection_field_to_selection_param ; $ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) ,) +] } ; (@ filters_to_args ;) => { vec ! [] } ; (@ filters_to_args ; $ ($ t : tt) *) => { $ ($ t) * } ; (@ field_serde_name ; id) => { "id" } ; (@ field_serde_name ; created_at) => { "createdAt" } ; (@ field_serde_name ; name) => { "name" } ; (@ field_serde_name ; unit) => { "unit" } ; (@ field_serde_name ; value) => { "value" } ; (@ field_serde_name ; device_id) => { "deviceId" } ; (@ field_serde_name ; measured_at) => { "measuredAt" } ; (@ field_serde_name ; sequence) => { "sequence" } ; }
 	pub use _select_sensor_reading as select;
 	pub enum SelectParam {
 		Id(id::Select),
@@ -341,6 +528,9 @@ pub mod sensor_reading {
 		Name(name::Select),
 		Unit(unit::Select),
 		Value(value::Select),
+		DeviceId(device_id::Select),
+		MeasuredAt(measured_at::Select),
+		Sequence(sequence::Select),
 	}
 	impl SelectParam {
 		pub fn to_selection(self) -> ::prisma_client_rust::Selection {
@@ -350,11 +540,14 @@ pub mod sensor_reading {
 				Self::Name(data) => data.to_selection(),
 				Self::Unit(data) => data.to_selection(),
 				Self::Value(data) => data.to_selection(),
+				Self::DeviceId(data) => data.to_selection(),
+				Self::MeasuredAt(data) => data.to_selection(),
+				Self::Sequence(data) => data.to_selection(),
 			}
 		}
 	}
 	#[macro_export]
-	macro_rules ! _include_sensor_reading { ($ (($ ($ func_arg : ident : $ func_arg_ty : ty) , +) =>) ? $ module_name : ident { $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { # [allow (warnings)] pub mod $ module_name { crate :: prisma :: sensor_reading :: include ! (@ definitions ; $ module_name ; $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) +) ; use super :: * ; pub struct Selection (Vec < :: prisma_client_rust :: Selection >) ; impl :: prisma_client_rust :: IncludeType for Selection { type Data = Data ; type ModelData = crate :: prisma :: sensor_reading :: Data ; fn to_selections (self) -> Vec < :: prisma_client_rust :: Selection > { self . 0 } } pub fn include ($ ($ ($ func_arg : $ func_arg_ty) , +) ?) -> Selection { Selection ([crate :: prisma :: sensor_reading :: include ! (@ selections_to_params ; : include { $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) + }) . into_iter () . map (| p | p . to_selection ()) . collect :: < Vec < _ >> () , < crate :: prisma :: sensor_reading :: Types as :: prisma_client_rust :: ModelTypes > :: scalar_selections ()] . into_iter () . flatten () . collect :: < Vec < _ >> ()) } } } ; ({ $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { { crate :: prisma :: sensor_reading :: include ! (@ definitions ; ; $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) +) ; pub struct Selection (Vec < :: prisma_client_rust :: Selection >) ; impl :: prisma_client_rust :: IncludeType for Selection { type Data = Data ; type ModelData = crate :: prisma :: sensor_reading :: Data ; fn to_selections (self) -> Vec < :: prisma_client_rust :: Selection > { self . 0 } } Selection ([crate :: prisma :: sensor_reading :: include ! (@ selections_to_params ; : include { $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) + }) . into_iter () . map (| p | p . to_selection ()) . collect :: < Vec < _ >> () , < crate :: prisma :: sensor_reading :: Types as :: prisma_client_rust :: ModelTypes > :: scalar_selections ()] . into_iter () . flatten () . collect :: < Vec < _ >> ()) } } ; (@ definitions ; $ ($ module_name : ident) ? ; $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) +) => { # [allow (warnings)] enum Fields { } # [allow (warnings)] impl Fields { fn selections () { $ (let _ = Fields :: $ field ;) + } } # [allow (warnings)] # [derive (std :: fmt :: Debug , Clone)] pub struct Data { pub id : String , pub created_at : :: prisma_client_rust :: chrono :: DateTime < :: prisma_client_rust :: chrono :: FixedOffset , > , pub name : String , pub unit : String , pub value : f64 , $ (pub $ field : crate :: prisma :: sensor_reading :: include ! (@ field_type ; $ field $ (: $ selection_mode { $ ($ selections) + }) ?) ,) + } impl :: serde :: Serialize for Data { fn serialize < S > (& self , serializer : S) -> Result < S :: Ok , S :: Error > where S : :: serde :: Serializer , { use :: serde :: ser :: SerializeStruct ; let mut state = serializer . serialize_struct ("Data" , [$ (stringify ! ($ field) ,) + stringify ! (id) , stringify ! (created_at) , stringify ! (name) , stringify ! (unit) , stringify ! (value)] . len ()) ? ; $ (state . serialize_field (crate :: prisma :: sensor_reading :: $ field :: NAME , & self . $ field) ? ;) * state . serialize_field (crate :: prisma :: sensor_reading :: id :: NAME , & self . id) ? ; state . serialize_field (crate :: prisma :: sensor_reading :: created_at :: NAME , & self . created_at) ? ; state . serialize_field (crate :: prisma :: sensor_reading :: name :: NAME , & self . name) ? ; state . serialize_field (crate :: prisma :: sensor_reading :: unit :: NAME , & self . unit) ? ; state . serialize_field (crate :: prisma :: sensor_reading :: value :: NAME , & self . value) ? ; state . end () } } impl < 'de > :: serde :: Deserialize < 'de > for Data { fn deserialize < D > (deserializer : D) -> Result < Self , D :: Error > where D : :: serde :: Deserializer < 'de > , { # [allow (warnings)] enum Field { $ ($ field) , + , id , created_at , name , unit , value } impl < 'de > :: serde :: Deserialize < 'de > for Field { fn deserialize < D > (deserializer : D) -> Result < Field , D :: Error > where D : :: serde :: Deserializer < 'de > , { struct FieldVisitor ; impl < 'de > :: serde :: de :: Visitor < 'de > for FieldVisitor { type Value = Field ; fn expecting (& self , formatter : & mut :: std :: fmt :: Formatter) -> :: std :: fmt :: Result { formatter . write_str (& [$ (crate :: prisma :: sensor_reading :: $ field :: NAME) , + , crate :: prisma :: sensor_reading :: id :: NAME , crate :: prisma :: sensor_reading :: created_at :: NAME , crate :: prisma :: sensor_reading :: name :: NAME , crate :: prisma :: sensor_reading :: unit :: NAME , crate :: prisma :: sensor_reading :: value :: NAME] . into_iter () . collect :: < Vec < _ >> () . join (", ")) } fn visit_str < E > (self , value : & str) -> Result < Field , E > where E : :: serde :: de :: Error , { match value { $ (crate :: prisma :: sensor_reading :: $ field :: NAME => Ok (Field :: $ field)) , * , crate :: prisma :: sensor_reading :: id :: NAME => Ok (Field :: id) , crate :: prisma :: sensor_reading :: created_at :: NAME => Ok (Field :: created_at) , crate :: prisma :: sensor_reading :: name :: NAME => Ok (Field :: name) , crate :: prisma :: sensor_reading :: unit :: NAME => Ok (Field :: unit) , crate :: prisma :: sensor_reading :: value :: NAME => Ok (Field :: value) , _ => Err (:: serde :: de :: Error :: unknown_field (value , FIELDS)) , } } } deserializer . deserialize_identifier (FieldVisitor) } } struct DataVisitor ; impl < 'de > :: serde :: de :: Visitor < 'de > for DataVisitor { type Value = Data ; fn expecting (& self , formatter : & mut std :: fmt :: Formatter) -> std :: fmt :: Result { formatter . write_str ("struct Data") } fn visit_map < V > (self , mut map : V) -> Result < Data , V :: Error > where V : :: serde :: de :: MapAccess < 'de > , { $ (let mut $ field = None ;) * let mut id = None ; let mut created_at = None ; let mut name = None ; let mut unit = None ; let mut value = None ; while let Some (key) = map . next_key () ? { match key { Field :: id => { if id . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: id :: NAME)) ; } id = Some (map . next_value () ?) ; } Field :: created_at => { if created_at . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: created_at :: NAME)) ; } created_at = Some (map . next_value () ?) ; } Field :: name => { if name . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: name :: NAME)) ; } name = Some (map . next_value () ?) ; } Field :: unit => { if unit . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: unit :: NAME)) ; } unit = Some (map . next_value () ?) ; } Field :: value => { if value . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: value :: NAME)) ; } value = Some (map . next_value () ?) ; } $ (Field :: $ field => { if $ field . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: $ field :: NAME)) ; } $ field = Some (map . next_value () ?) ; }) * } } $ (let $ field = $ field . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: $ field :: NAME)) ? ;) * let id = id . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: id :: NAME)) ? ; let created_at = created_at . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: created_at :: NAME)) ? ; let name = name . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: name :: NAME)) ? ; let unit = unit . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: unit :: NAME)) ? ; let value = value . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: value :: NAME)) ? ; Ok (Data { id , created_at , name , unit , value , $ ($ field) , * }) } } const FIELDS : & 'static [& 'static str] = & ["id" , "createdAt" , "name" , "unit" , "value"] ; deserializer . deserialize_struct ("Data" , FIELDS , DataVisitor) } } $ ($ (pub mod $ field { crate :: prisma :: sensor_reading :: $ selection_mode ! (@ field_module ; $ field : $ selection_mode { $ ($ selections) + }) ; }) ?) + } ; (@ field_type ; $ field : ident $ ($ tokens : tt) *) => { compile_error ! (stringify ! (Cannot include nonexistent relation $ field on model "SensorReading" , available relations are "")) } ; (@ field_module ; $ ($ tokens : tt) *) => { } ; (@ selection_field_to_selection_param ; $ ($ tokens : tt) *) => { compile_error ! (stringify ! ($ ($ tokens) *)) } ; (@ selections_to_params ; : $ macro_name : ident { $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { [$ (crate :: prisma :: sensor_reading :: $ macro_name ! (@ selection_field_to_selection_param ; $ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) ,) +] } ; (@ filters_to_args ;) => { vec ! [] } ; (@ filters_to_args ; $ ($ t : tt) *) => { $ ($ t) * } ; (@ field_serde_name ; id) => { "id" } ; (@ field_serde_name ; created_at) => { "createdAt" } ; (@ field_serde_name ; name) => { "name" } ; (@ field_serde_name ; unit) => { "unit" } ; (@ field_serde_name ; value) => { "value" } ; }
+	macro_rules ! _include_sensor_reading { ($ (($ ($ func_arg : ident : $ func_arg_ty : ty) , +) =>) ? $ module_name : ident { $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { # [allow (warnings)] pub mod $ module_name { crate :: prisma :: sensor_reading :: include ! (@ definitions ; $ module_name ; $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) +) ; use super :: * ; pub struct Selection (Vec < :: prisma_client_rust :: Selection >) ; impl :: prisma_client_rust :: IncludeType for Selection { type Data = Data ; type ModelData = crate :: prisma :: sensor_reading :: Data ; fn to_selections (self) -> Vec < :: prisma_client_rust :: Selection > { self . 0 } } pub fn include ($ ($ ($ func_arg : $ func_arg_ty) , +) ?) -> Selection { Selection ([crate :: prisma :: sensor_reading :: include ! (@ selections_to_params ; : include { $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) + }) . into_iter () . map (| p | p . to_selection ()) . collect :: < Vec < _ >> () , < crate :: prisma :: sensor_reading :: Types as :: prisma_client_rust :: ModelTypes > :: scalar_selections ()] . into_iter () . flatten () . collect :: < Vec < _ >> ()) } } } ; ({ $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { { crate :: prisma :: sensor_reading :: include ! (@ definitions ; ; $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) +) ; pub struct Selection (Vec < :: prisma_client_rust :: Selection >) ; impl :: prisma_client_rust :: IncludeType for Selection { type Data = Data ; type ModelData = crate :: prisma :: sensor_reading :: Data ; fn to_selections (self) -> Vec < :: prisma_client_rust :: Selection > { self . 0 } } Selection ([crate :: prisma :: sensor_reading :: include ! (@ selections_to_params ; : include { $ ($ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) + }) . into_iter () . map (| p | p . to_selection ()) . collect :: < Vec < _ >> () , < crate :: prisma :: sensor_reading :: Types as :: prisma_client_rust :: ModelTypes > :: scalar_selections ()] . into_iter () . flatten () . collect :: < Vec < _ >> ()) } } ; (@ definitions ; $ ($ module_name : ident) ? ; $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) +) => { # [allow (warnings)] enum Fields { } # [allow (warnings)] impl Fields { fn selections () { $ (let _ = Fields :: $ field ;) + } } # [allow (warnings)] # [derive (std :: fmt :: Debug , Clone)] pub struct Data { pub id : String , pub created_at : :: prisma_client_rust :: chrono :: DateTime < :: prisma_client_rust :: chrono :: FixedOffset , > , pub name : String , pub unit : String , pub value : f64 , pub device_id : String , pub measured_at : :: prisma_client_rust :: chrono :: DateTime < :: prisma_client_rust :: chrono :: FixedOffset , > , pub sequence : i64 , $ (pub $ field : crate :: prisma :: sensor_reading :: include ! (@ field_type ; $ field $ (: $ selection_mode { $ ($ selections) + }) ?) ,) + } impl :: serde :: Serialize for Data { fn serialize < S > (& self , serializer : S) -> Result < S :: Ok , S :: Error > where S : :: serde :: Serializer , { use :: serde :: ser :: SerializeStruct ; let mut state = serializer . serialize_struct ("Data" , [$ (stringify ! ($ field) ,) + stringify ! (id) , stringify ! (created_at) , stringify ! (name) , stringify ! (unit) , stringify ! (value) , stringify ! (device_id) , stringify ! (measured_at) , stringify ! (sequence)] . len ()) ? ; $ (state . serialize_field (crate :: prisma :: sensor_reading :: $ field :: NAME , & self . $ field) ? ;) * state . serialize_field (crate :: prisma :: sensor_reading :: id :: NAME , & self . id) ? ; state . serialize_field (crate :: prisma :: sensor_reading :: created_at :: NAME , & self . created_at) ? ; state . serialize_field (crate :: prisma :: sensor_reading :: name :: NAME , & self . name) ? ; state . serialize_field (crate :: prisma :: sensor_reading :: unit :: NAME , & self . unit) ? ; state . serialize_field (crate :: prisma :: sensor_reading :: value :: NAME , & self . value) ? ; state . serialize_field (crate :: prisma :: sensor_reading :: device_id :: NAME , & self . device_id) ? ; state . serialize_field (crate :: prisma :: sensor_reading :: measured_at :: NAME , & self . measured_at) ? ; state . serialize_field (crate :: prisma :: sensor_reading :: sequence :: NAME , & self . sequence) ? ; state . end () } } impl < 'de > :: serde :: Deserialize < 'de > for Data { fn deserialize < D > (deserializer : D) -> Result < Self , D :: Error > where D : :: serde :: Deserializer < 'de > , { # [allow (warnings)] enum Field { $ ($ field) , + , id , created_at , name , unit , value , device_id , measured_at , sequence } impl < 'de > :: serde :: Deserialize < 'de > for Field { fn deserialize < D > (deserializer : D) -> Result < Field , D :: Error > where D : :: serde :: Deserializer < 'de > , { struct FieldVisitor ; impl < 'de > :: serde :: de :: Visitor < 'de > for FieldVisitor { type Value = Field ; fn expecting (& self , formatter : & mut :: std :: fmt :: Formatter) -> :: std :: fmt :: Result { formatter . write_str (& [$ (crate :: prisma :: sensor_reading :: $ field :: NAME) , + , crate :: prisma :: sensor_reading :: id :: NAME , crate :: prisma :: sensor_reading :: created_at :: NAME , crate :: prisma :: sensor_reading :: name :: NAME , crate :: prisma :: sensor_reading :: unit :: NAME , crate :: prisma :: sensor_reading :: value :: NAME , crate :: prisma :: sensor_reading :: device_id :: NAME , crate :: prisma :: sensor_reading :: measured_at :: NAME , crate :: prisma :: sensor_reading :: sequence :: NAME] . into_iter () . collect :: < Vec < _ >> () . join (", ")) } fn visit_str < E > (self , value : & str) -> Result < Field , E > where E : :: serde :: de :: Error , { match value { $ (crate :: prisma :: sensor_reading :: $ field :: NAME => Ok (Field :: $ field)) , * , crate :: prisma :: sensor_reading :: id :: NAME => Ok (Field :: id) , crate :: prisma :: sensor_reading :: created_at :: NAME => Ok (Field :: created_at) , crate :: prisma :: sensor_reading :: name :: NAME => Ok (Field :: name) , crate :: prisma :: sensor_reading :: unit :: NAME => Ok (Field :: unit) , crate :: prisma :: sensor_reading :: value :: NAME => Ok (Field :: value) , crate :: prisma :: sensor_reading :: device_id :: NAME => Ok (Field :: device_id) , crate :: prisma :: sensor_reading :: measured_at :: NAME => Ok (Field :: measured_at) , crate :: prisma :: sensor_reading :: sequence :: NAME => Ok (Field :: sequence) , _ => Err (:: serde :: de :: Error :: unknown_field (value , FIELDS)) , } } } deserializer . deserialize_identifier (FieldVisitor) } } struct DataVisitor ; impl < 'de > :: serde :: de :: Visitor < 'de > for DataVisitor { type Value = Data ; fn expecting (& self , formatter : & mut std :: fmt :: Formatter) -> std :: fmt :: Result { formatter . write_str ("struct Data") } fn visit_map < V > (self , mut map : V) -> Result < Data , V :: Error > where V : :: serde :: de :: MapAccess < 'de > , { $ (let mut $ field = None ;) * let mut id = None ; let mut created_at = None ; let mut name = None ; let mut unit = None ; let mut value = None ; let mut device_id = None ; let mut measured_at = None ; let mut sequence = None ; while let Some (key) = map . next_key () ? { match key { Field :: id => { if id . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: id :: NAME)) ; } id = Some (map . next_value () ?) ; } Field :: created_at => { if created_at . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: created_at :: NAME)) ; } created_at = Some (map . next_value () ?) ; } Field :: name => { if name . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: name :: NAME)) ; } name = Some (map . next_value () ?) ; } Field :: unit => { if unit . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: unit :: NAME)) ; } unit = Some (map . next_value () ?) ; } Field :: value => { if value . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: value :: NAME)) ; } value = Some (map . next_value () ?) ; } Field :: device_id => { if device_id . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: device_id :: NAME)) ; } device_id = Some (map . next_value () ?) ; } Field :: measured_at => { if measured_at . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: measured_at :: NAME)) ; } measured_at = Some (map . next_value () ?) ; } Field :: sequence => { if sequence . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: sequence :: NAME)) ; } sequence = Some (map . next_value () ?) ; } $ (Field :: $ field => { if $ field . is_some () { return Err (:: serde :: de :: Error :: duplicate_field (crate :: prisma :: sensor_reading :: $ field :: NAME)) ; } $ field = Some (map . next_value () ?) ; }) * } } $ (let $ field = $ field . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: $ field :: NAME)) ? ;) * let id = id . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: id :: NAME)) ? ; let created_at = created_at . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: created_at :: NAME)) ? ; let name = name . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: name :: NAME)) ? ; let unit = unit . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: unit :: NAME)) ? ; let value = value . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: value :: NAME)) ? ; let device_id = device_id . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: device_id :: NAME)) ? ; let measured_at = measured_at . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: measured_at :: NAME)) ? ; let sequence = sequence . ok_or_else (|| serde :: de :: Error :: missing_field (crate :: prisma :: sensor_reading :: sequence :: NAME)) ? ; Ok (Data { id , created_at , name , unit , value , device_id , measured_at , sequence , $ ($ field) , * }) } } const FIELDS : & 'static [& 'static str] = & ["id" , "createdAt" , "name" , "unit" , "value" , "deviceId" , "measuredAt" , "sequence"] ; deserializer . deserialize_struct ("Data" , FIELDS , DataVisitor) } } $ ($ (pub mod $ field { crate :: prisma :: sensor_reading :: $ selection_mode ! (@ field_module ; $ field : $ selection_mode { $ ($ selections) + }) ; }) ?) + } ; (@ field_type ; $ field : ident $ ($ tokens : tt) *) => { compile_error ! (stringify ! (Cannot include nonexistent relation $ field on model "SensorReading" , available relations are "")) } ; (@ field_module ; $ ($ tokens : tt) *) => { } ; (@ selection_field_to_selection_param ; $ ($ tokens : tt) *) => { compile_error ! (stringify ! ($ ($ tokens) *)) } ; (@ selections_to_params ; : $ macro_name : ident { $ ($ field : ident $ (($ ($ filters : tt) +) $ (. $ arg : ident ($ ($ arg_params : tt) *)) *) ? $ (: $ selection_mode : ident { $ ($ selections : tt) + }) ?) + }) => { [$ (crate :: prisma :: sensor_reading :: $ macro_name ! (@ selection_field_to_selection_param ; $ field $ (($ ($ filters) +) $ (. $ arg ($ ($ arg_params) *)) *) ? $ (: $ selection_mode { $ ($ selections) + }) ?) ,) +] } ; (@ filters_to_args ;) => { vec ! [] } ; (@ filters_to_args ; $ ($ t : tt) *) => { $ ($ t) * } ; (@ field_serde_name ; id) => { "id" } ; (@ field_serde_name ; created_at) => { "createdAt" } ; (@ field_serde_name ; name) => { "name" } ; (@ field_serde_name ; unit) => { "unit" } ; (@ field_serde_name ; value) => { "value" } ; (@ field_serde_name ; device_id) => { "deviceId" } ; (@ field_serde_name ; measured_at) => { "measuredAt" } ; (@ field_serde_name ; sequence) => { "sequence" } ; }
 	pub use _include_sensor_reading as include;
 	pub enum IncludeParam {
 		Id(id::Include),
@@ -362,6 +555,9 @@ pub mod sensor_reading {
 		Name(name::Include),
 		Unit(unit::Include),
 		Value(value::Include),
+		DeviceId(device_id::Include),
+		MeasuredAt(measured_at::Include),
+		Sequence(sequence::Include),
 	}
 	impl IncludeParam {
 		pub fn to_selection(self) -> ::prisma_client_rust::Selection {
@@ -371,11 +567,14 @@ pub mod sensor_reading {
 				Self::Name(data) => data.to_selection(),
 				Self::Unit(data) => data.to_selection(),
 				Self::Value(data) => data.to_selection(),
+				Self::DeviceId(data) => data.to_selection(),
+				Self::MeasuredAt(data) => data.to_selection(),
+				Self::Sequence(data) => data.to_selection(),
 			}
 		}
 	}
 	#[macro_export]
-	macro_rules ! _partial_unchecked_sensor_reading { ($ struct_name : ident { $ ($ scalar_field : ident) + }) => { :: prisma_client_rust :: macros :: partial_unchecked ! { crate :: prisma :: sensor_reading struct $ struct_name { # [serde (rename = "id")] pub id : String , # [serde (rename = "createdAt")] pub created_at : :: prisma_client_rust :: chrono :: DateTime < :: prisma_client_rust :: chrono :: FixedOffset , > , # [serde (rename = "name")] pub name : String , # [serde (rename = "unit")] pub unit : String , # [serde (rename = "value")] pub value : f64 } [$ ($ scalar_field) , +] } } ; }
+	macro_rules ! _partial_unchecked_sensor_reading { ($ struct_name : ident { $ ($ scalar_field : ident) + }) => { :: prisma_client_rust :: macros :: partial_unchecked ! { crate :: prisma :: sensor_reading struct $ struct_name { # [serde (rename = "id")] pub id : String , # [serde (rename = "createdAt")] pub created_at : :: prisma_client_rust :: chrono :: DateTime < :: prisma_client_rust :: chrono :: FixedOffset , > , # [serde (rename = "name")] pub name : String , # [serde (rename = "unit")] pub unit : String , # [serde (rename = "value")] pub value : f64 , # [serde (rename = "deviceId")] pub device_id : String , # [serde (rename = "measuredAt")] pub measured_at : :: prisma_client_rust :: chrono :: DateTime < :: prisma_client_rust :: chrono :: FixedOffset , > , # [serde (rename = "sequence")] pub sequence : i64 } [$ ($ scalar_field) , +] } } ; }
 	pub use _partial_unchecked_sensor_reading as partial_unchecked;
 	#[derive(Debug, Clone, :: serde :: Serialize, :: serde :: Deserialize)]
 	pub struct Data {
@@ -389,6 +588,12 @@ pub mod sensor_reading {
 		pub unit: String,
 		#[serde(rename = "value")]
 		pub value: f64,
+		#[serde(rename = "deviceId")]
+		pub device_id: String,
+		#[serde(rename = "measuredAt")]
+		pub measured_at: ::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>,
+		#[serde(rename = "sequence")]
+		pub sequence: i64,
 	}
 	impl Data {}
 	#[derive(Clone)]
@@ -409,10 +614,17 @@ pub mod sensor_reading {
 		DecrementValue(f64),
 		MultiplyValue(f64),
 		DivideValue(f64),
+		SetDeviceId(String),
+		SetMeasuredAt(::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>),
+		SetSequence(i64),
+		IncrementSequence(i64),
+		DecrementSequence(i64),
+		MultiplySequence(i64),
+		DivideSequence(i64),
 	}
 	impl From<SetParam> for (String, ::prisma_client_rust::PrismaValue) {
 		fn from(param: SetParam) -> Self {
-			match param { SetParam :: SetId (value) => (id :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: String (value)) , SetParam :: SetCreatedAt (value) => (created_at :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: DateTime (value)) , SetParam :: SetName (value) => (name :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: String (value)) , SetParam :: SetUnit (value) => (unit :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: String (value)) , SetParam :: SetValue (value) => (value :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ())) , SetParam :: IncrementValue (value) => (value :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("increment" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))])) , SetParam :: DecrementValue (value) => (value :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("decrement" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))])) , SetParam :: MultiplyValue (value) => (value :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("multiply" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))])) , SetParam :: DivideValue (value) => (value :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("divide" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))])) }
+			match param { SetParam :: SetId (value) => (id :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: String (value)) , SetParam :: SetCreatedAt (value) => (created_at :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: DateTime (value)) , SetParam :: SetName (value) => (name :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: String (value)) , SetParam :: SetUnit (value) => (unit :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: String (value)) , SetParam :: SetValue (value) => (value :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ())) , SetParam :: IncrementValue (value) => (value :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("increment" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))])) , SetParam :: DecrementValue (value) => (value :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("decrement" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))])) , SetParam :: MultiplyValue (value) => (value :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("multiply" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))])) , SetParam :: DivideValue (value) => (value :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("divide" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))])) , SetParam :: SetDeviceId (value) => (device_id :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: String (value)) , SetParam :: SetMeasuredAt (value) => (measured_at :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: DateTime (value)) , SetParam :: SetSequence (value) => (sequence :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: BigInt (value)) , SetParam :: IncrementSequence (value) => (sequence :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("increment" . to_string () , :: prisma_client_rust :: PrismaValue :: BigInt (value))])) , SetParam :: DecrementSequence (value) => (sequence :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("decrement" . to_string () , :: prisma_client_rust :: PrismaValue :: BigInt (value))])) , SetParam :: MultiplySequence (value) => (sequence :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("multiply" . to_string () , :: prisma_client_rust :: PrismaValue :: BigInt (value))])) , SetParam :: DivideSequence (value) => (sequence :: NAME . to_string () , :: prisma_client_rust :: PrismaValue :: Object (vec ! [("divide" . to_string () , :: prisma_client_rust :: PrismaValue :: BigInt (value))])) }
 		}
 	}
 	#[derive(Clone)]
@@ -422,6 +634,9 @@ pub mod sensor_reading {
 		Name(String),
 		Unit(String),
 		Value(f64),
+		DeviceId(String),
+		MeasuredAt(::prisma_client_rust::chrono::DateTime<::prisma_client_rust::chrono::FixedOffset>),
+		Sequence(i64),
 	}
 	impl From<UncheckedSetParam> for SetParam {
 		fn from(param: UncheckedSetParam) -> Self {
@@ -431,6 +646,9 @@ pub mod sensor_reading {
 				UncheckedSetParam::Name(value) => Self::SetName(value),
 				UncheckedSetParam::Unit(value) => Self::SetUnit(value),
 				UncheckedSetParam::Value(value) => Self::SetValue(value),
+				UncheckedSetParam::DeviceId(value) => Self::SetDeviceId(value),
+				UncheckedSetParam::MeasuredAt(value) => Self::SetMeasuredAt(value),
+				UncheckedSetParam::Sequence(value) => Self::SetSequence(value),
 			}
 		}
 	}
@@ -441,6 +659,9 @@ pub mod sensor_reading {
 		Name(::prisma_client_rust::Direction),
 		Unit(::prisma_client_rust::Direction),
 		Value(::prisma_client_rust::Direction),
+		DeviceId(::prisma_client_rust::Direction),
+		MeasuredAt(::prisma_client_rust::Direction),
+		Sequence(::prisma_client_rust::Direction),
 	}
 	impl Into<(String, ::prisma_client_rust::PrismaValue)> for OrderByParam {
 		fn into(self) -> (String, ::prisma_client_rust::PrismaValue) {
@@ -458,6 +679,15 @@ pub mod sensor_reading {
 				Self::Value(direction) => {
 					(value::NAME.to_string(), ::prisma_client_rust::PrismaValue::String(direction.to_string()))
 				}
+				Self::DeviceId(direction) => {
+					(device_id::NAME.to_string(), ::prisma_client_rust::PrismaDeviceId::String(direction.to_string()))
+				}
+				Self::MeasuredAt(direction) => {
+					(measured_at::NAME.to_string(), ::prisma_client_rust::PrismaMeasuredAt::String(direction.to_string()))
+				}
+				Self::Sequence(direction) => {
+					(sequence::NAME.to_string(), ::prisma_client_rust::PrismaSequence::String(direction.to_string()))
+				}
 			}
 		}
 	}
@@ -471,6 +701,9 @@ pub mod sensor_reading {
 		Name(_prisma::read_filters::StringFilter),
 		Unit(_prisma::read_filters::StringFilter),
 		Value(_prisma::read_filters::FloatFilter),
+		DeviceId(_prisma::read_filters::StringFilter),
+		MeasuredAt(_prisma::read_filters::DateTimeFilter),
+		Sequence(_prisma::read_filters::BigIntFilter),
 	}
 	impl ::prisma_client_rust::WhereInput for WhereParam {
 		fn serialize(self) -> ::prisma_client_rust::SerializedWhereInput {
@@ -504,6 +737,9 @@ pub mod sensor_reading {
 				Self::Name(value) => (name::NAME, value.into()),
 				Self::Unit(value) => (unit::NAME, value.into()),
 				Self::Value(value) => (value::NAME, value.into()),
+				Self::DeviceId(value) => (device_id::NAME, value.into()),
+				Self::MeasuredAt(value) => (measured_at::NAME, value.into()),
+				Self::Sequence(value) => (sequence::NAME, value.into()),
 			};
 			::prisma_client_rust::SerializedWhereInput::new(name, value.into())
 		}
@@ -546,6 +782,9 @@ pub mod sensor_reading {
 				::prisma_client_rust::sel(name::NAME),
 				::prisma_client_rust::sel(unit::NAME),
 				::prisma_client_rust::sel(value::NAME),
+				::prisma_client_rust::sel(device_id::NAME),
+				::prisma_client_rust::sel(measured_at::NAME),
+				::prisma_client_rust::sel(sequence::NAME),
 			]
 		}
 	}
@@ -715,6 +954,12 @@ pub mod _prisma {
 		Unit,
 		#[serde(rename = "value")]
 		Value,
+		#[serde(rename = "deviceId")]
+		DeviceId,
+		#[serde(rename = "measuredAt")]
+		MeasuredAt,
+		#[serde(rename = "sequence")]
+		Sequence,
 	}
 	impl ToString for SensorReadingScalarFieldEnum {
 		fn to_string(&self) -> String {
@@ -724,6 +969,9 @@ pub mod _prisma {
 				Self::Name => "name".to_string(),
 				Self::Unit => "unit".to_string(),
 				Self::Value => "value".to_string(),
+				Self::DeviceId => "deviceId".to_string(),
+				Self::MeasuredAt => "measuredAt".to_string(),
+				Self::Sequence => "sequence".to_string(),
 			}
 		}
 	}
@@ -885,6 +1133,22 @@ pub mod _prisma {
 				match self { Self :: Equals (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("equals" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))]) , Self :: InVec (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("in" . to_string () , :: prisma_client_rust :: PrismaValue :: List (value . into_iter () . map (| value | :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ())) . collect ()))]) , Self :: NotInVec (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("notIn" . to_string () , :: prisma_client_rust :: PrismaValue :: List (value . into_iter () . map (| value | :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ())) . collect ()))]) , Self :: Lt (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("lt" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))]) , Self :: Lte (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("lte" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))]) , Self :: Gt (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("gt" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))]) , Self :: Gte (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("gte" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))]) , Self :: Not (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("not" . to_string () , :: prisma_client_rust :: PrismaValue :: Float (< :: prisma_client_rust :: bigdecimal :: BigDecimal as :: prisma_client_rust :: bigdecimal :: FromPrimitive > :: from_f64 (value) . unwrap () . normalized ()))]) }
 			}
 		}
+		#[derive(Clone)]
+		pub enum BigIntFilter {
+			Equals(i64),
+			InVec(Vec<i64>),
+			NotInVec(Vec<i64>),
+			Lt(i64),
+			Lte(i64),
+			Gt(i64),
+			Gte(i64),
+			Not(i64),
+		}
+		impl Into<::prisma_client_rust::SerializedWhereValue> for BigIntFilter {
+			fn into(self) -> ::prisma_client_rust::SerializedWhereValue {
+				match self { Self :: Equals (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("equals" . to_string () , :: prisma_client_rust :: PrismaValue :: BigInt (value))]) , Self :: InVec (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("in" . to_string () , :: prisma_client_rust :: PrismaValue :: List (value . into_iter () . map (| value | :: prisma_client_rust :: PrismaValue :: BigInt (value)) . collect ()))]) , Self :: NotInVec (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("notIn" . to_string () , :: prisma_client_rust :: PrismaValue :: List (value . into_iter () . map (| value | :: prisma_client_rust :: PrismaValue :: BigInt (value)) . collect ()))]) , Self :: Lt (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("lt" . to_string () , :: prisma_client_rust :: PrismaValue :: BigInt (value))]) , Self :: Lte (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("lte" . to_string () , :: prisma_client_rust :: PrismaValue :: BigInt (value))]) , Self :: Gt (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("gt" . to_string () , :: prisma_client_rust :: PrismaValue :: BigInt (value))]) , Self :: Gte (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("gte" . to_string () , :: prisma_client_rust :: PrismaValue :: BigInt (value))]) , Self :: Not (value) => :: prisma_client_rust :: SerializedWhereValue :: Object (vec ! [("not" . to_string () , :: prisma_client_rust :: PrismaValue :: BigInt (value))]) }
+			}
+		}
 	}
 }
 pub use _prisma::*;
@@ -0,0 +1,58 @@
+use crate::source::MessageSource;
+use common::message::Origin;
+use common::mqtt::{validate_filter, MqttClient, ReconnectOptions};
+use common::utils::dotenv;
+use std::error::Error;
+use std::time::Duration;
+
+const DEFAULT_CLIENT_ID: &str = "subscriber";
+const SUBSCRIPTION_QOS: i32 = 1;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct Mqtt {
+	client: MqttClient,
+	reconnect: ReconnectOptions,
+	topic: Option<String>,
+}
+
+impl Mqtt {
+	pub fn new() -> Result<Self, Box<dyn Error>> {
+		let client_id = dotenv::parse_var::<String>("SUBSCRIBER_CLIENT_ID").unwrap_or(DEFAULT_CLIENT_ID.to_owned());
+
+		let mut client = MqttClient::new(&client_id)?.without_status_topic();
+		// Without a clean session the broker keeps the messages published while the subscriber is offline
+		client.connect(Duration::from_secs(60), false)?;
+
+		Ok(Mqtt { client, reconnect: ReconnectOptions::from_env(), topic: None })
+	}
+
+	// Topics are a comma separated list of topic filters, like the ones forwarded by the bridge
+	pub fn subscribe(&self, topics: &str) -> Result<(), Box<dyn Error>> {
+		for topic in topics.split(',').map(str::trim).filter(|topic| !topic.is_empty()) {
+			validate_filter(topic).map_err(|e| format!("Invalid topic filter {}: {}", topic, e))?;
+			self.client.subscribe(topic, SUBSCRIPTION_QOS)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl MessageSource for Mqtt {
+	async fn next(&mut self) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+		loop {
+			// The MQTT client blocks while waiting, so the runtime moves its other tasks off this thread
+			if let Some(message) = tokio::task::block_in_place(|| self.client.wait_for_message(POLL_INTERVAL)) {
+				self.topic = Some(message.topic().to_owned());
+				return Ok(Some(message.payload().to_vec()));
+			}
+
+			tokio::task::block_in_place(|| self.client.ensure_connected(&self.reconnect));
+			// Lets the pipeline flush its batch between two polls
+			tokio::task::yield_now().await;
+		}
+	}
+
+	fn origin(&self) -> Origin {
+		Origin { topic: self.topic.clone(), ..Origin::default() }
+	}
+}
@@ -0,0 +1,246 @@
+use common::kafka::KafkaMessage;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::error::Error;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::Mutex;
+use tokio::sync::mpsc::Sender;
+
+// The messages of a partition, or of a key, are always handled by the same worker, in the order they are read
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OrderBy {
+	#[default]
+	Partition,
+	Key,
+}
+
+impl OrderBy {
+	// Messages without a key are ordered by partition
+	pub fn worker(&self, message: &KafkaMessage, workers: usize) -> usize {
+		let mut hasher = DefaultHasher::new();
+		match (self, &message.key) {
+			(OrderBy::Key, Some(key)) => key.hash(&mut hasher),
+			_ => (&message.topic, message.partition).hash(&mut hasher),
+		}
+
+		(hasher.finish() % workers as u64) as usize
+	}
+}
+
+impl FromStr for OrderBy {
+	type Err = String;
+
+	fn from_str(order: &str) -> Result<Self, Self::Err> {
+		match order {
+			"partition" => Ok(OrderBy::Partition),
+			"key" => Ok(OrderBy::Key),
+			_ => Err(format!("unknown order {}, expected partition or key", order)),
+		}
+	}
+}
+
+#[derive(Default)]
+struct PartitionOffsets {
+	pending: BTreeSet<i64>,
+	next: i64,
+	committed: i64,
+}
+
+// With the messages of a partition spread over several workers, a partition is only committed up to its first pending message
+#[derive(Default)]
+pub struct OffsetTracker {
+	partitions: HashMap<(String, i32), PartitionOffsets>,
+}
+
+impl OffsetTracker {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn read(&mut self, topic: &str, partition: i32, offset: i64) {
+		let offsets = self.partitions.entry((topic.to_owned(), partition)).or_default();
+
+		offsets.pending.insert(offset);
+		offsets.next = offsets.next.max(offset + 1);
+	}
+
+	pub fn handled(&mut self, topic: &str, partition: i32, offset: i64) {
+		if let Some(offsets) = self.partitions.get_mut(&(topic.to_owned(), partition)) {
+			offsets.pending.remove(&offset);
+		}
+	}
+
+	// The partitions that moved forward since the last call, with the offset to commit them until
+	pub fn committable(&mut self) -> Vec<(String, i32, i64)> {
+		let mut committable = Vec::new();
+		for ((topic, partition), offsets) in &mut self.partitions {
+			let offset = offsets.pending.first().copied().unwrap_or(offsets.next);
+			if offset > offsets.committed {
+				offsets.committed = offset;
+				committable.push((topic.clone(), *partition, offset));
+			}
+		}
+
+		committable
+	}
+
+	// The offset to commit every partition until, moved forward or not, for the last commit before stopping
+	pub fn positions(&mut self) -> Vec<(String, i32, i64)> {
+		let mut positions = Vec::new();
+		for ((topic, partition), offsets) in &mut self.partitions {
+			let offset = offsets.pending.first().copied().unwrap_or(offsets.next);
+			offsets.committed = offsets.committed.max(offset);
+			positions.push((topic.clone(), *partition, offset));
+		}
+
+		positions
+	}
+}
+
+// A worker with a full queue stops the reading, so a slow worker slows down the consumption instead of filling the memory
+pub async fn dispatch<F, Fut, E>(
+	mut receive: F,
+	order_by: OrderBy,
+	workers: &[Sender<KafkaMessage>],
+	offsets: &Mutex<OffsetTracker>,
+	shutdown: impl Future<Output = ()>,
+) -> Result<(), Box<dyn Error>>
+where
+	F: FnMut() -> Fut,
+	Fut: Future<Output = Result<KafkaMessage, E>>,
+	E: Error + 'static,
+{
+	tokio::pin!(shutdown);
+
+	loop {
+		let message = tokio::select! {
+			message = receive() => message?,
+			_ = &mut shutdown => return Ok(()),
+		};
+		offsets.lock().unwrap().read(&message.topic, message.partition, message.offset);
+
+		let worker = order_by.worker(&message, workers.len());
+		if workers[worker].send(message).await.is_err() {
+			return Err(format!("Worker {} stopped", worker).into());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_offset_tracker() {
+		let mut tracker = OffsetTracker::new();
+		for offset in 10..15 {
+			tracker.read("sensors", 0, offset);
+		}
+		tracker.read("sensors", 1, 3);
+
+		let mut committable = tracker.committable();
+		committable.sort();
+		assert_eq!(committable, [("sensors".to_owned(), 0, 10), ("sensors".to_owned(), 1, 3)]);
+
+		tracker.handled("sensors", 0, 11);
+		tracker.handled("sensors", 0, 12);
+		assert!(tracker.committable().is_empty(), "Offset 10 should still be pending");
+
+		tracker.handled("sensors", 0, 10);
+		tracker.handled("sensors", 1, 3);
+		let mut committable = tracker.committable();
+		committable.sort();
+		assert_eq!(committable, [("sensors".to_owned(), 0, 13), ("sensors".to_owned(), 1, 4)]);
+		assert!(tracker.committable().is_empty(), "Partitions should only be committed once they move forward");
+
+		tracker.handled("sensors", 0, 14);
+		tracker.handled("sensors", 0, 13);
+		assert_eq!(tracker.committable(), [("sensors".to_owned(), 0, 15)]);
+
+		let mut positions = tracker.positions();
+		positions.sort();
+		assert_eq!(positions, [("sensors".to_owned(), 0, 15), ("sensors".to_owned(), 1, 4)]);
+		assert!(tracker.committable().is_empty());
+	}
+
+	fn message(partition: i32, offset: i64, key: Option<&str>) -> KafkaMessage {
+		KafkaMessage {
+			payload: Vec::new(),
+			key: key.map(|key| key.as_bytes().to_vec()),
+			topic: "sensors".to_owned(),
+			partition,
+			offset,
+			timestamp: None,
+		}
+	}
+
+	#[test]
+	fn test_order_by() {
+		let message = |partition: i32, key: Option<&str>| message(partition, 0, key);
+
+		for workers in 1..8 {
+			for order_by in [OrderBy::Partition, OrderBy::Key] {
+				let worker = order_by.worker(&message(3, Some("station-1")), workers);
+				assert!(worker < workers);
+				assert_eq!(order_by.worker(&message(3, Some("station-1")), workers), worker);
+			}
+
+			let by_key = OrderBy::Key.worker(&message(0, Some("station-1")), workers);
+			assert_eq!(OrderBy::Key.worker(&message(5, Some("station-1")), workers), by_key);
+			assert_eq!(OrderBy::Key.worker(&message(5, None), workers), OrderBy::Partition.worker(&message(5, None), workers));
+		}
+
+		assert_eq!("key".parse::<OrderBy>(), Ok(OrderBy::Key));
+		assert!("device".parse::<OrderBy>().is_err());
+	}
+
+	#[tokio::test]
+	async fn test_dispatch() {
+		let mut messages: std::collections::VecDeque<_> =
+			(0..5).flat_map(|offset| (0..4).map(move |partition| message(partition, offset, None))).collect();
+		let receive = || {
+			let message = messages.pop_front();
+			async move {
+				match message {
+					Some(message) => Ok::<_, std::convert::Infallible>(message),
+					None => std::future::pending().await,
+				}
+			}
+		};
+
+		let (senders, mut receivers): (Vec<_>, Vec<_>) = (0..3).map(|_| tokio::sync::mpsc::channel(100)).unzip();
+		let offsets = Mutex::new(OffsetTracker::new());
+		let shutdown = tokio::time::sleep(std::time::Duration::from_millis(50));
+		dispatch(receive, OrderBy::Partition, &senders, &offsets, shutdown).await.expect("Dispatch should stop on shutdown");
+		drop(senders);
+
+		let mut handled = Vec::new();
+		for (worker, receiver) in receivers.iter_mut().enumerate() {
+			let mut read = HashMap::new();
+			while let Some(message) = receiver.recv().await {
+				assert_eq!(OrderBy::Partition.worker(&message, 3), worker);
+				let next = read.entry(message.partition).or_insert(0);
+				assert_eq!(message.offset, *next, "The messages of a partition should reach their worker in order");
+				*next += 1;
+			}
+			assert!(read.values().all(|read| *read == 5));
+			handled.push(read.into_keys().collect::<Vec<_>>());
+		}
+		assert_eq!(handled.iter().map(Vec::len).sum::<usize>(), 4, "Each partition should go to a single worker");
+
+		let mut offsets = offsets.into_inner().unwrap();
+		let worker = handled.iter().position(|partitions| !partitions.is_empty()).unwrap();
+		for &partition in &handled[worker] {
+			for offset in 0..5 {
+				offsets.handled("sensors", partition, offset);
+			}
+		}
+		let mut committable = offsets.committable();
+		committable.sort();
+		let mut expected: Vec<_> = handled[worker].iter().map(|partition| ("sensors".to_owned(), *partition, 5)).collect();
+		expected.sort();
+		assert_eq!(committable, expected, "Only the partitions handled by the worker should be committed");
+	}
+}
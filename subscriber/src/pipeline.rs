@@ -0,0 +1,592 @@
+use crate::availability::AvailabilityTracker;
+use crate::sequence::SequenceTracker;
+use crate::source::MessageSource;
+use crate::throughput::Throughput;
+use common::message::{DeadLetter, Origin, SensorMessage, StatusMessage};
+use common::mqtt::ReconnectOptions;
+use common::utils::dotenv;
+use std::error::Error;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const DEFAULT_STORE_ATTEMPTS: u32 = 10;
+const DEFAULT_BATCH_SIZE: usize = 100;
+const DEFAULT_FLUSH_INTERVAL: f64 = 1.0;
+const MAX_FLUSH_INTERVAL: f64 = 86400.0;
+const REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+pub trait MessageSink {
+	async fn store(&mut self, message: SensorMessage) -> Result<(), Box<dyn Error>>;
+
+	// Readings the sink can never store are rejected before they are buffered, instead of failing their whole batch
+	fn validate(&self, _message: &SensorMessage) -> Result<(), String> {
+		Ok(())
+	}
+
+	async fn store_many(&mut self, messages: Vec<SensorMessage>) -> Result<(), Box<dyn Error>> {
+		for message in messages {
+			self.store(message).await?;
+		}
+
+		Ok(())
+	}
+}
+
+impl MessageSink for Vec<SensorMessage> {
+	async fn store(&mut self, message: SensorMessage) -> Result<(), Box<dyn Error>> {
+		self.push(message);
+		Ok(())
+	}
+}
+
+pub trait DeadLetterSink {
+	async fn send(&mut self, letter: DeadLetter) -> Result<(), Box<dyn Error>>;
+}
+
+impl DeadLetterSink for Vec<DeadLetter> {
+	async fn send(&mut self, letter: DeadLetter) -> Result<(), Box<dyn Error>> {
+		self.push(letter);
+		Ok(())
+	}
+}
+
+// A reading waiting for its batch to be stored, with what is needed to dead letter it
+struct Buffered {
+	message: SensorMessage,
+	payload: String,
+	origin: Origin,
+}
+
+pub struct Pipeline<K, D> {
+	sink: K,
+	dead_letters: Option<D>,
+	tracker: Arc<Mutex<SequenceTracker>>,
+	availability: Arc<Mutex<AvailabilityTracker>>,
+	retry: ReconnectOptions,
+	store_attempts: u32,
+	batch_size: usize,
+	flush_interval: Duration,
+	batch: Vec<Buffered>,
+	flush_at: Option<tokio::time::Instant>,
+	throughput: Throughput,
+}
+
+impl<K: MessageSink, D: DeadLetterSink> Pipeline<K, D> {
+	pub fn new(sink: K, dead_letters: Option<D>) -> Self {
+		Pipeline {
+			sink,
+			dead_letters,
+			tracker: Arc::new(Mutex::new(SequenceTracker::new())),
+			availability: Arc::new(Mutex::new(AvailabilityTracker::new())),
+			retry: ReconnectOptions::default(),
+			store_attempts: dotenv::parse_var("SUBSCRIBER_STORE_ATTEMPTS").unwrap_or(DEFAULT_STORE_ATTEMPTS).max(1),
+			batch_size: dotenv::parse_var("SUBSCRIBER_BATCH_SIZE").unwrap_or(DEFAULT_BATCH_SIZE).max(1),
+			flush_interval: flush_interval(dotenv::parse_var("SUBSCRIBER_FLUSH_INTERVAL")),
+			batch: Vec::new(),
+			flush_at: None,
+			throughput: Throughput::new(Instant::now()),
+		}
+	}
+
+	// Pipelines running side by side share the trackers, as the messages of a device can reach any of them when the
+	// Kafka key is not the device id
+	pub fn with_trackers(mut self, tracker: Arc<Mutex<SequenceTracker>>, availability: Arc<Mutex<AvailabilityTracker>>) -> Self {
+		self.tracker = tracker;
+		self.availability = availability;
+		self
+	}
+
+	pub async fn run(&mut self, source: &mut impl MessageSource) -> Result<(), Box<dyn Error>> {
+		self.run_until(source, std::future::pending()).await
+	}
+
+	// Messages are committed only once every message before them is stored, skipped or dead lettered, so a failing
+	// database stops the pipeline before losing them, and the source is not read while a batch is being stored
+	pub async fn run_until(
+		&mut self,
+		source: &mut impl MessageSource,
+		shutdown: impl Future<Output = ()>,
+	) -> Result<(), Box<dyn Error>> {
+		tokio::pin!(shutdown);
+
+		loop {
+			// Nothing is awaited inside the select, so the pipeline can still be moved to another thread while it waits
+			let flush_at = self.flush_at;
+			let received = tokio::select! {
+				payload = source.next() => Some(payload?),
+				_ = tokio::time::sleep_until(flush_at.unwrap_or_else(tokio::time::Instant::now)), if flush_at.is_some() => None,
+				_ = &mut shutdown => {
+					println!("Shutting down, storing {} buffered sensor readings", self.batch.len());
+					break;
+				}
+			};
+
+			match received {
+				Some(Some(payload)) => {
+					self.handle(&payload, &source.origin()).await?;
+				}
+				Some(None) => break,
+				None => self.flush().await?,
+			}
+
+			if self.batch.is_empty() {
+				commit(source).await;
+			}
+		}
+
+		self.flush().await?;
+		if let Err(e) = source.close().await {
+			eprintln!("Failed to commit the handled messages: {}", e);
+		}
+		println!("{}", self.throughput.summary(Instant::now()));
+		Ok(())
+	}
+
+	// Status messages share the topic of the readings, so they are told apart before decoding a reading
+	pub async fn handle(&mut self, payload: &[u8], origin: &Origin) -> Result<bool, Box<dyn Error>> {
+		let payload = match std::str::from_utf8(payload) {
+			Ok(payload) => payload,
+			Err(e) => {
+				eprintln!("Failed to deserialize message: {}", e);
+				self.dead_letter(payload, &e.to_string(), origin).await?;
+				return Ok(false);
+			}
+		};
+
+		if let Ok(status) = StatusMessage::decode(payload) {
+			self.availability.lock().unwrap().update(&status);
+			return Ok(false);
+		}
+
+		let message = match SensorMessage::decode(payload) {
+			Ok(message) => message,
+			Err(e) => {
+				eprintln!("Failed to deserialize message: {}", e);
+				self.dead_letter(payload.as_bytes(), &e.to_string(), origin).await?;
+				return Ok(false);
+			}
+		};
+
+		if let Err(e) = self.sink.validate(&message) {
+			eprintln!("Invalid sensor reading: {}", e);
+			self.dead_letter(payload.as_bytes(), &e, origin).await?;
+			return Ok(false);
+		}
+
+		self.availability.lock().unwrap().check(&message.device_id);
+		if !self.tracker.lock().unwrap().reserve(&message) {
+			return Ok(false);
+		}
+
+		self.batch.push(Buffered { message, payload: payload.to_owned(), origin: origin.clone() });
+		if self.batch.len() >= self.batch_size {
+			self.flush().await?;
+		} else if self.flush_at.is_none() {
+			self.flush_at = Some(tokio::time::Instant::now() + self.flush_interval);
+		}
+		Ok(true)
+	}
+
+	pub async fn flush(&mut self) -> Result<(), Box<dyn Error>> {
+		self.flush_at = None;
+		if self.batch.is_empty() {
+			return Ok(());
+		}
+
+		let started = Instant::now();
+		let messages = self.batch.iter().map(|buffered| buffered.message.clone()).collect();
+		let stored = match self.store(messages).await {
+			Ok(()) => std::mem::take(&mut self.batch),
+			Err(error) => self.store_each(error).await?,
+		};
+		if stored.is_empty() {
+			return Ok(());
+		}
+
+		let mut tracker = self.tracker.lock().unwrap();
+		for buffered in &stored {
+			tracker.record(&buffered.message);
+		}
+		drop(tracker);
+
+		let now = Instant::now();
+		self.throughput.record(stored.len(), now - started);
+		println!("{} sensor readings added to the database in {:.1}ms", stored.len(), (now - started).as_secs_f64() * 1000.0);
+
+		if self.throughput.elapsed(now) >= REPORT_INTERVAL {
+			println!("{}", self.throughput.summary(now));
+		}
+		Ok(())
+	}
+
+	// After a failed batch the readings are stored one at a time, without retrying, so only the failing ones are
+	// dead lettered, or stop the pipeline before they are committed when there is no dead letter topic
+	async fn store_each(&mut self, error: String) -> Result<Vec<Buffered>, Box<dyn Error>> {
+		let batch = std::mem::take(&mut self.batch);
+		let single = batch.len() == 1;
+		if !single {
+			eprintln!("{}, storing the readings one at a time", error);
+		}
+
+		let mut stored = Vec::new();
+		for buffered in batch {
+			let result = if single {
+				Err(error.clone())
+			} else {
+				self.sink.store(buffered.message.clone()).await.map_err(|e| e.to_string())
+			};
+
+			match result {
+				Ok(()) => stored.push(buffered),
+				Err(e) if self.dead_letters.is_none() => return Err(e.into()),
+				Err(e) => {
+					eprintln!("Failed to add a sensor reading to the database: {}", e);
+					self.tracker.lock().unwrap().release(&buffered.message);
+					self.dead_letter(buffered.payload.as_bytes(), &e, &buffered.origin).await?;
+				}
+			}
+		}
+
+		Ok(stored)
+	}
+
+	async fn dead_letter(&mut self, payload: &[u8], error: &str, origin: &Origin) -> Result<(), Box<dyn Error>> {
+		if let Some(dead_letters) = &mut self.dead_letters {
+			dead_letters.send(DeadLetter::new(payload, error, origin.clone())).await?;
+		}
+
+		Ok(())
+	}
+
+	// A single reading goes through the single insert path, so both can be compared with the same batch sizes
+	// Errors are kept as strings, so they are not held while waiting to retry
+	async fn store(&mut self, messages: Vec<SensorMessage>) -> Result<(), String> {
+		let mut attempt = 0;
+		loop {
+			let stored = match messages.len() {
+				1 => self.sink.store(messages[0].clone()).await.map_err(|e| e.to_string()),
+				_ => self.sink.store_many(messages.clone()).await.map_err(|e| e.to_string()),
+			};
+			let Err(e) = stored else {
+				return Ok(());
+			};
+
+			attempt += 1;
+			if attempt >= self.store_attempts {
+				return Err(format!("Failed to add sensor readings to the database after {} attempts: {}", attempt, e));
+			}
+
+			let delay = self.retry.delay(attempt - 1, &mut rand::thread_rng());
+			eprintln!("Failed to add sensor readings to the database, retrying in {:.1}s: {}", delay.as_secs_f64(), e);
+			tokio::time::sleep(delay).await;
+		}
+	}
+}
+
+async fn commit(source: &mut impl MessageSource) {
+	if let Err(e) = source.commit().await {
+		eprintln!("Failed to commit the handled messages: {}", e);
+	}
+}
+
+// Intervals out of range fall back to the default, instead of overflowing the flush deadline
+fn flush_interval(seconds: Option<f64>) -> Duration {
+	let seconds = seconds.filter(|seconds| (0.0..=MAX_FLUSH_INTERVAL).contains(seconds)).unwrap_or(DEFAULT_FLUSH_INTERVAL);
+	Duration::from_secs_f64(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use common::message::{Availability, PayloadEncoding};
+	use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+	fn reading(sequence: u64) -> String {
+		format!(
+			r#"{{"device_id": "station-1", "sensor": "SPS30", "value": 0.69, "unit": "ug/m3", "timestamp": "2024-01-01T00:00:{:02}Z", "sequence": {}}}"#,
+			sequence % 60,
+			sequence
+		)
+	}
+
+	#[tokio::test]
+	async fn test_pipeline() {
+		let (sender, mut source) = mpsc::unbounded_channel();
+		for payload in [
+			StatusMessage::new("station-1", Availability::Online).encode(),
+			reading(1),
+			reading(2),
+			reading(2),
+			r#"{"device_id": "station-1", "sensor""#.to_owned(),
+			reading(3),
+		] {
+			sender.send(payload).unwrap();
+		}
+		drop(sender);
+
+		let mut pipeline = Pipeline::new(Vec::new(), Some(Vec::new()));
+		pipeline.run(&mut source).await.expect("Pipeline should drain the source");
+
+		assert_eq!(pipeline.sink.iter().map(|message| message.sequence).collect::<Vec<_>>(), [Some(1), Some(2), Some(3)]);
+		assert_eq!(pipeline.availability.lock().unwrap().status("station-1"), Some(Availability::Online));
+
+		let dead_letters = pipeline.dead_letters.expect("Dead letters should be kept");
+		assert_eq!(dead_letters.len(), 1);
+		assert_eq!(dead_letters[0].payload, r#"{"device_id": "station-1", "sensor""#);
+		assert!(dead_letters[0].error.starts_with("invalid sensor message"));
+	}
+
+	#[tokio::test]
+	async fn test_binary_dead_letter() {
+		let payload = [0x7b, 0xff, 0x7d];
+		let mut pipeline = Pipeline::new(Vec::new(), Some(Vec::new()));
+		pipeline.handle(&payload, &Origin::default()).await.expect("Invalid payloads should be dead lettered");
+
+		let dead_letters = pipeline.dead_letters.unwrap();
+		assert_eq!(dead_letters[0].encoding, PayloadEncoding::Hex);
+		assert_eq!(dead_letters[0].payload_bytes(), Some(payload.to_vec()), "The original bytes should be kept for a re-drive");
+	}
+
+	struct FlakySink {
+		failures: u32,
+		stored: Vec<SensorMessage>,
+	}
+
+	impl MessageSink for FlakySink {
+		async fn store(&mut self, message: SensorMessage) -> Result<(), Box<dyn Error>> {
+			if self.failures > 0 {
+				self.failures -= 1;
+				return Err("database unavailable".into());
+			}
+
+			self.stored.push(message);
+			Ok(())
+		}
+	}
+
+	#[tokio::test]
+	async fn test_store_retries() {
+		let reading = reading(1);
+
+		let origin = Origin::default();
+		let pipeline = |failures: u32, dead_letters: Option<Vec<DeadLetter>>| {
+			let mut pipeline = Pipeline::new(FlakySink { failures, stored: Vec::new() }, dead_letters);
+			pipeline.retry.min_delay = Duration::ZERO;
+			pipeline.store_attempts = 3;
+			pipeline.batch_size = 1;
+			pipeline
+		};
+
+		let mut stored = pipeline(2, None);
+		assert!(stored.handle(reading.as_bytes(), &origin).await.expect("The third attempt should store the reading"));
+		assert_eq!(stored.sink.stored.len(), 1);
+
+		let mut failed = pipeline(3, None);
+		assert!(failed.handle(reading.as_bytes(), &origin).await.is_err());
+		assert!(failed.sink.stored.is_empty());
+
+		let mut dead_lettered = pipeline(3, Some(Vec::new()));
+		dead_lettered.handle(reading.as_bytes(), &origin).await.expect("The reading should be dead lettered");
+		assert!(dead_lettered.dead_letters.unwrap()[0].error.ends_with("database unavailable"));
+	}
+
+	#[tokio::test]
+	async fn test_redrive() {
+		let origin = Origin::default();
+		let mut pipeline = Pipeline::new(FlakySink { failures: 0, stored: Vec::new() }, Some(Vec::new()));
+		pipeline.retry.min_delay = Duration::ZERO;
+		pipeline.store_attempts = 1;
+		pipeline.batch_size = 1;
+
+		for sequence in 1..=4 {
+			pipeline.sink.failures = (sequence % 2) as u32;
+			pipeline.handle(reading(sequence).as_bytes(), &origin).await.expect("Failed readings should be dead lettered");
+		}
+		let dead_letters = pipeline.dead_letters.replace(Vec::new()).unwrap();
+		assert_eq!(dead_letters.len(), 2);
+
+		for letter in dead_letters {
+			let payload = letter.payload_bytes().unwrap();
+			pipeline.handle(&payload, &origin).await.expect("Re-driven readings should be stored");
+		}
+		pipeline.handle(reading(5).as_bytes(), &origin).await.unwrap();
+		pipeline.handle(reading(3).as_bytes(), &origin).await.unwrap();
+
+		let stored: Vec<_> = pipeline.sink.stored.iter().map(|message| message.sequence.unwrap()).collect();
+		assert_eq!(stored, [2, 4, 1, 3, 5], "Re-driven readings should be stored once, without restarting the sequence");
+		assert!(pipeline.dead_letters.unwrap().is_empty());
+	}
+
+	#[tokio::test]
+	async fn test_shared_trackers() {
+		let origin = Origin::default();
+		let tracker = Arc::new(Mutex::new(SequenceTracker::new()));
+		let availability = Arc::new(Mutex::new(AvailabilityTracker::new()));
+		let mut first = Pipeline::new(Vec::new(), Some(Vec::new())).with_trackers(tracker.clone(), availability.clone());
+		let mut second = Pipeline::new(Vec::new(), Some(Vec::new())).with_trackers(tracker.clone(), availability.clone());
+		let mut failing = Pipeline::new(FlakySink { failures: 1, stored: Vec::new() }, Some(Vec::new()))
+			.with_trackers(tracker.clone(), availability.clone());
+		failing.retry.min_delay = Duration::ZERO;
+		failing.store_attempts = 1;
+		failing.batch_size = 1;
+
+		assert!(first.handle(reading(2).as_bytes(), &origin).await.unwrap());
+		assert!(
+			!second.handle(reading(2).as_bytes(), &origin).await.unwrap(),
+			"A reading buffered by another pipeline should be skipped"
+		);
+		first.flush().await.unwrap();
+		assert!(!second.handle(reading(2).as_bytes(), &origin).await.unwrap());
+
+		failing.handle(reading(3).as_bytes(), &origin).await.unwrap();
+		assert_eq!(failing.dead_letters.as_ref().unwrap().len(), 1);
+		assert!(second.handle(reading(3).as_bytes(), &origin).await.unwrap(), "A dead lettered reading should be accepted again");
+		second.flush().await.unwrap();
+
+		let stored: Vec<_> = first.sink.iter().chain(&second.sink).map(|message| message.sequence.unwrap()).collect();
+		assert_eq!(stored, [2, 3], "Each reading should be stored once across the pipelines");
+	}
+
+	struct BatchSink(Vec<Vec<Option<u64>>>);
+
+	impl MessageSink for BatchSink {
+		async fn store(&mut self, message: SensorMessage) -> Result<(), Box<dyn Error>> {
+			self.store_many(vec![message]).await
+		}
+
+		async fn store_many(&mut self, messages: Vec<SensorMessage>) -> Result<(), Box<dyn Error>> {
+			self.0.push(messages.iter().map(|message| message.sequence).collect());
+			Ok(())
+		}
+	}
+
+	// Records how many messages were read at each commit
+	struct CommitSource {
+		receiver: UnboundedReceiver<String>,
+		read: usize,
+		commits: Vec<usize>,
+		closed: bool,
+	}
+
+	impl MessageSource for CommitSource {
+		async fn next(&mut self) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+			let payload = self.receiver.next().await?;
+			self.read += payload.is_some() as usize;
+			Ok(payload)
+		}
+
+		async fn commit(&mut self) -> Result<(), Box<dyn Error>> {
+			self.commits.push(self.read);
+			Ok(())
+		}
+
+		async fn close(&mut self) -> Result<(), Box<dyn Error>> {
+			self.closed = true;
+			self.commit().await
+		}
+	}
+
+	#[tokio::test]
+	async fn test_batches() {
+		let (sender, receiver) = mpsc::unbounded_channel();
+		for sequence in 1..=5 {
+			sender.send(reading(sequence)).unwrap();
+		}
+		drop(sender);
+
+		let mut source = CommitSource { receiver, read: 0, commits: Vec::new(), closed: false };
+		let mut pipeline = Pipeline::new(BatchSink(Vec::new()), None::<Vec<DeadLetter>>);
+		pipeline.batch_size = 2;
+		pipeline.run(&mut source).await.expect("Pipeline should drain the source");
+
+		assert_eq!(pipeline.sink.0, [vec![Some(1), Some(2)], vec![Some(3), Some(4)], vec![Some(5)]]);
+		assert_eq!(source.commits, [2, 4, 5]);
+		assert!(source.closed, "The last commit should close the source");
+	}
+
+	// Fails every batch holding a reading with a sequence of 3, and can not store sequences above 100
+	struct PickySink(Vec<u64>);
+
+	impl MessageSink for PickySink {
+		async fn store(&mut self, message: SensorMessage) -> Result<(), Box<dyn Error>> {
+			self.store_many(vec![message]).await
+		}
+
+		async fn store_many(&mut self, messages: Vec<SensorMessage>) -> Result<(), Box<dyn Error>> {
+			let sequences: Vec<_> = messages.iter().filter_map(|message| message.sequence).collect();
+			if sequences.contains(&3) {
+				return Err("invalid reading 3".into());
+			}
+
+			self.0.extend(sequences);
+			Ok(())
+		}
+
+		fn validate(&self, message: &SensorMessage) -> Result<(), String> {
+			match message.sequence {
+				Some(sequence) if sequence > 100 => Err(format!("sequence {} is too large", sequence)),
+				_ => Ok(()),
+			}
+		}
+	}
+
+	#[tokio::test]
+	async fn test_batch_fallback() {
+		let (sender, mut source) = mpsc::unbounded_channel();
+		for sequence in [1, 2, 1000, 3, 4, 5] {
+			sender.send(reading(sequence)).unwrap();
+		}
+		drop(sender);
+
+		let mut pipeline = Pipeline::new(PickySink(Vec::new()), Some(Vec::new()));
+		pipeline.retry.min_delay = Duration::ZERO;
+		pipeline.store_attempts = 2;
+		pipeline.batch_size = 3;
+		pipeline.run(&mut source).await.expect("Pipeline should drain the source");
+
+		assert_eq!(pipeline.sink.0, [1, 2, 4, 5], "Only the failing reading should be left out of its batch");
+		let dead_letters = pipeline.dead_letters.unwrap();
+		assert_eq!(
+			dead_letters.iter().map(|letter| letter.error.as_str()).collect::<Vec<_>>(),
+			["sequence 1000 is too large", "invalid reading 3"]
+		);
+
+		let mut pipeline = Pipeline::new(PickySink(Vec::new()), None::<Vec<DeadLetter>>);
+		pipeline.batch_size = 2;
+		pipeline.store_attempts = 1;
+		pipeline.handle(reading(3).as_bytes(), &Origin::default()).await.expect("The reading should be buffered");
+		assert!(pipeline.handle(reading(4).as_bytes(), &Origin::default()).await.is_err());
+		assert!(pipeline.sink.0.is_empty(), "Without dead letters the pipeline should stop at the failing reading");
+	}
+
+	#[tokio::test]
+	async fn test_flush_interval() {
+		let (sender, receiver) = mpsc::unbounded_channel();
+		sender.send(reading(1)).unwrap();
+
+		let mut source = CommitSource { receiver, read: 0, commits: Vec::new(), closed: false };
+		let mut pipeline = Pipeline::new(BatchSink(Vec::new()), None::<Vec<DeadLetter>>);
+		pipeline.flush_interval = Duration::from_millis(20);
+		pipeline
+			.run_until(&mut source, tokio::time::sleep(Duration::from_millis(200)))
+			.await
+			.expect("Pipeline should stop on shutdown");
+
+		assert_eq!(pipeline.sink.0, [vec![Some(1)]]);
+		assert_eq!(source.commits, [1, 1], "The reading should be committed once flushed, before the shutdown");
+		assert!(source.closed);
+		drop(sender);
+	}
+
+	#[test]
+	fn test_flush_interval_range() {
+		assert_eq!(flush_interval(Some(0.5)), Duration::from_millis(500));
+		assert_eq!(flush_interval(Some(0.0)), Duration::ZERO);
+		assert_eq!(flush_interval(Some(MAX_FLUSH_INTERVAL)), Duration::from_secs(86400));
+
+		for seconds in [None, Some(-1.0), Some(f64::NAN), Some(f64::INFINITY), Some(1e19), Some(2e19)] {
+			assert_eq!(flush_interval(seconds), Duration::from_secs(1), "Expected the default for {:?}", seconds);
+		}
+	}
+}
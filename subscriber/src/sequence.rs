@@ -0,0 +1,211 @@
+use chrono::{DateTime, Utc};
+use common::message::SensorMessage;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+const MAX_MISSING: usize = 10000;
+
+#[derive(Debug, PartialEq)]
+pub enum Delivery {
+	First,
+	InOrder,
+	Gap { missing: u64 },
+	Late,
+	Duplicate,
+	Restarted,
+}
+
+struct DeviceSequence {
+	first: u64,
+	next: u64,
+	missing: BTreeSet<u64>,
+	latest: Option<DateTime<Utc>>,
+}
+
+impl DeviceSequence {
+	fn new(sequence: u64, at: Option<DateTime<Utc>>) -> Self {
+		DeviceSequence { first: sequence, next: sequence.saturating_add(1), missing: BTreeSet::new(), latest: at }
+	}
+}
+
+// Readings are reserved before they are stored and recorded after, so a reading that was dead lettered instead of
+// stored is still accepted when it is re-driven, while the pipelines sharing the tracker accept a redelivered one once
+#[derive(Default)]
+pub struct SequenceTracker {
+	devices: HashMap<String, DeviceSequence>,
+	reserved: HashSet<(String, u64)>,
+}
+
+impl SequenceTracker {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn reserve(&mut self, message: &SensorMessage) -> bool {
+		let Some(sequence) = message.sequence else {
+			return true;
+		};
+
+		if self.classify(&message.device_id, sequence, message.timestamp) == Delivery::Duplicate
+			|| !self.reserved.insert((message.device_id.clone(), sequence))
+		{
+			eprintln!("Skipping duplicate reading {} from device {}", sequence, message.device_id);
+			return false;
+		}
+		true
+	}
+
+	// For a reading that could not be stored, so it is accepted again
+	pub fn release(&mut self, message: &SensorMessage) {
+		if let Some(sequence) = message.sequence {
+			self.reserved.remove(&(message.device_id.clone(), sequence));
+		}
+	}
+
+	pub fn record(&mut self, message: &SensorMessage) {
+		let Some(sequence) = message.sequence else {
+			return;
+		};
+		let device_id = &message.device_id;
+		self.reserved.remove(&(device_id.clone(), sequence));
+
+		match self.track_at(device_id, sequence, message.timestamp) {
+			Delivery::Gap { missing } => {
+				eprintln!("Missing {} readings from device {} before sequence {}", missing, device_id, sequence)
+			}
+			Delivery::Late => eprintln!("Out of order reading {} from device {}", sequence, device_id),
+			Delivery::Restarted => println!("Device {} restarted its sequence", device_id),
+			Delivery::First | Delivery::InOrder | Delivery::Duplicate => {}
+		}
+	}
+
+	pub fn track(&mut self, device_id: &str, sequence: u64) -> Delivery {
+		self.track_at(device_id, sequence, None)
+	}
+
+	fn track_at(&mut self, device_id: &str, sequence: u64, at: Option<DateTime<Utc>>) -> Delivery {
+		let delivery = self.classify(device_id, sequence, at);
+		let Some(device) = self.devices.get_mut(device_id) else {
+			self.devices.insert(device_id.to_owned(), DeviceSequence::new(sequence, at));
+			return delivery;
+		};
+
+		match delivery {
+			Delivery::InOrder => device.next = device.next.saturating_add(1),
+			Delivery::Gap { .. } => {
+				let oldest = sequence.saturating_sub(MAX_MISSING as u64).max(device.next);
+				device.missing.extend(oldest..sequence);
+				while device.missing.len() > MAX_MISSING {
+					device.missing.pop_first();
+				}
+				device.next = sequence.saturating_add(1);
+			}
+			Delivery::Late => {
+				device.missing.remove(&sequence);
+			}
+			Delivery::Restarted => *device = DeviceSequence::new(sequence, None),
+			Delivery::First | Delivery::Duplicate => {}
+		}
+		device.latest = device.latest.max(at);
+
+		delivery
+	}
+
+	// Readings before the first one seen were never stored, and an older reading 1, like a re-driven one, is no restart
+	fn classify(&self, device_id: &str, sequence: u64, at: Option<DateTime<Utc>>) -> Delivery {
+		let Some(device) = self.devices.get(device_id) else {
+			return Delivery::First;
+		};
+		let older = at.is_some_and(|at| device.latest.is_some_and(|latest| at < latest));
+
+		if sequence == device.next {
+			Delivery::InOrder
+		} else if sequence > device.next {
+			Delivery::Gap { missing: sequence - device.next }
+		} else if device.missing.contains(&sequence) || (sequence < device.first && (sequence != 1 || older)) {
+			Delivery::Late
+		} else if sequence == 1 && !older {
+			Delivery::Restarted
+		} else {
+			Delivery::Duplicate
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_sequence_tracking() {
+		let mut tracker = SequenceTracker::new();
+		let deliveries: Vec<_> = [5, 6, 7, 10, 8, 8, 11, 9, 12].iter().map(|sequence| tracker.track("station-1", *sequence)).collect();
+
+		assert_eq!(
+			deliveries,
+			[
+				Delivery::First,
+				Delivery::InOrder,
+				Delivery::InOrder,
+				Delivery::Gap { missing: 2 },
+				Delivery::Late,
+				Delivery::Duplicate,
+				Delivery::InOrder,
+				Delivery::Late,
+				Delivery::InOrder,
+			]
+		);
+	}
+
+	#[test]
+	fn test_devices_are_independent() {
+		let mut tracker = SequenceTracker::new();
+
+		assert_eq!(tracker.track("station-1", 1), Delivery::First);
+		assert_eq!(tracker.track("station-2", 1), Delivery::First);
+		assert_eq!(tracker.track("station-1", 2), Delivery::InOrder);
+		assert_eq!(tracker.track("station-2", 3), Delivery::Gap { missing: 1 });
+		assert_eq!(tracker.track("station-1", 1), Delivery::Restarted, "Sequence 1 should be treated as a device restart");
+		assert_eq!(tracker.track("station-1", 2), Delivery::InOrder);
+	}
+
+	#[test]
+	fn test_large_sequences() {
+		let mut tracker = SequenceTracker::new();
+
+		assert_eq!(tracker.track("station-1", 1), Delivery::First);
+		assert_eq!(tracker.track("station-1", u64::MAX - 1), Delivery::Gap { missing: u64::MAX - 3 });
+		assert_eq!(tracker.devices["station-1"].missing.len(), MAX_MISSING);
+		assert_eq!(tracker.track("station-1", u64::MAX), Delivery::InOrder);
+		assert_eq!(tracker.track("station-1", u64::MAX - 2), Delivery::Late);
+		assert_eq!(tracker.track("station-2", u64::MAX), Delivery::First);
+	}
+
+	#[test]
+	fn test_recorded_readings() {
+		let message = |sequence: u64, second: u32| {
+			let timestamp = format!("2024-01-01T00:00:{:02}Z", second);
+			SensorMessage::decode(&format!(
+				r#"{{"device_id": "station-1", "sensor": "SPS30", "value": 1, "unit": "ug/m3", "timestamp": "{}", "sequence": {}}}"#,
+				timestamp, sequence
+			))
+			.unwrap()
+		};
+		let mut tracker = SequenceTracker::new();
+
+		tracker.record(&message(2, 2));
+		assert!(tracker.reserve(&message(3, 3)), "Reserving should not record the reading");
+		assert!(!tracker.reserve(&message(3, 3)), "A reserved reading should only be accepted once");
+		tracker.record(&message(4, 4));
+		assert!(!tracker.reserve(&message(4, 4)));
+
+		tracker.release(&message(3, 3));
+		assert!(tracker.reserve(&message(3, 3)), "A released reading that was never stored should be accepted");
+		tracker.record(&message(3, 3));
+		assert!(!tracker.reserve(&message(3, 3)));
+
+		assert!(tracker.reserve(&message(1, 1)), "A reading older than the first one seen should be accepted");
+		assert_eq!(tracker.track_at("station-1", 1, Some(message(1, 1).timestamp.unwrap())), Delivery::Late);
+		assert_eq!(tracker.track("station-1", 5), Delivery::InOrder, "An older reading 1 should not restart the sequence");
+		assert_eq!(tracker.track_at("station-1", 1, Some(message(1, 10).timestamp.unwrap())), Delivery::Restarted);
+	}
+}
@@ -0,0 +1,190 @@
+use crate::partition::OffsetTracker;
+use common::kafka::{Kafka, KafkaMessage};
+use common::message::Origin;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Lines};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{Receiver, UnboundedReceiver};
+
+// Yields the raw payloads of the messages, None once the source is exhausted
+pub trait MessageSource {
+	async fn next(&mut self) -> Result<Option<Vec<u8>>, Box<dyn Error>>;
+
+	// Where the last message came from, kept along with it when it is dead lettered
+	fn origin(&self) -> Origin {
+		Origin::default()
+	}
+
+	// Called once every message read so far is handled, for sources that can deliver them again otherwise
+	async fn commit(&mut self) -> Result<(), Box<dyn Error>> {
+		Ok(())
+	}
+
+	// The last commit, once the source is exhausted or the pipeline shuts down
+	async fn close(&mut self) -> Result<(), Box<dyn Error>> {
+		self.commit().await
+	}
+}
+
+// Where the handled offsets are committed, Kafka outside of the tests
+pub trait OffsetCommitter: Send + Sync + 'static {
+	fn commit(&self, topic: &str, partition: i32, offset: i64, sync: bool) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+impl OffsetCommitter for Kafka {
+	fn commit(&self, topic: &str, partition: i32, offset: i64, sync: bool) -> Result<(), Box<dyn Error + Send + Sync>> {
+		if sync {
+			self.commit_until_sync(topic, partition, offset)?;
+		} else {
+			self.commit_until(topic, partition, offset)?;
+		}
+		Ok(())
+	}
+}
+
+// The Kafka messages dispatched to one of the workers, which share the offsets of the partitions
+pub struct WorkerSource<C = Kafka> {
+	receiver: Receiver<KafkaMessage>,
+	kafka: Arc<C>,
+	offsets: Arc<Mutex<OffsetTracker>>,
+	read: Vec<(String, i32, i64)>,
+	origin: Origin,
+}
+
+impl<C: OffsetCommitter> WorkerSource<C> {
+	pub fn new(receiver: Receiver<KafkaMessage>, kafka: Arc<C>, offsets: Arc<Mutex<OffsetTracker>>) -> Self {
+		WorkerSource { receiver, kafka, offsets, read: Vec::new(), origin: Origin::default() }
+	}
+}
+
+impl<C: OffsetCommitter> MessageSource for WorkerSource<C> {
+	async fn next(&mut self) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+		let Some(mut message) = self.receiver.recv().await else {
+			return Ok(None);
+		};
+		let payload = std::mem::take(&mut message.payload);
+
+		self.origin = message.origin();
+		self.read.push((message.topic, message.partition, message.offset));
+		Ok(Some(payload))
+	}
+
+	fn origin(&self) -> Origin {
+		self.origin.clone()
+	}
+
+	// The offsets stay locked while committing, so the commits of the workers reach Kafka in order
+	async fn commit(&mut self) -> Result<(), Box<dyn Error>> {
+		let mut offsets = self.offsets.lock().unwrap();
+		for (topic, partition, offset) in self.read.drain(..) {
+			offsets.handled(&topic, partition, offset);
+		}
+
+		for (topic, partition, offset) in offsets.committable() {
+			self.kafka.commit(&topic, partition, offset, false).map_err(|e| e.to_string())?;
+		}
+		Ok(())
+	}
+
+	// The async commits sent before may still be lost when the process exits, so every partition is committed again,
+	// waiting for Kafka away from the runtime threads and without holding the offsets
+	async fn close(&mut self) -> Result<(), Box<dyn Error>> {
+		let positions = {
+			let mut offsets = self.offsets.lock().unwrap();
+			for (topic, partition, offset) in self.read.drain(..) {
+				offsets.handled(&topic, partition, offset);
+			}
+			offsets.positions()
+		};
+
+		let kafka = self.kafka.clone();
+		tokio::task::spawn_blocking(move || {
+			positions.iter().try_for_each(|(topic, partition, offset)| kafka.commit(topic, *partition, *offset, true))
+		})
+		.await?
+		.map_err(|e| e.to_string())?;
+		Ok(())
+	}
+}
+
+impl MessageSource for UnboundedReceiver<String> {
+	async fn next(&mut self) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+		Ok(self.recv().await.map(String::into_bytes))
+	}
+}
+
+// Replays one payload per line, like a JSON lines export of the Kafka topic
+pub struct ReplaySource {
+	lines: Lines<Box<dyn BufRead>>,
+}
+
+impl ReplaySource {
+	pub fn open(path: &str) -> io::Result<Self> {
+		let reader: Box<dyn BufRead> =
+			if path == "-" { Box::new(io::stdin().lock()) } else { Box::new(BufReader::new(File::open(path)?)) };
+
+		Ok(ReplaySource { lines: reader.lines() })
+	}
+}
+
+impl MessageSource for ReplaySource {
+	async fn next(&mut self) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+		for line in self.lines.by_ref() {
+			let line = line?;
+			if !line.trim().is_empty() {
+				return Ok(Some(line.into_bytes()));
+			}
+		}
+
+		Ok(None)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tokio::sync::mpsc;
+
+	#[derive(Default)]
+	struct Commits(Mutex<Vec<(i32, i64, bool)>>);
+
+	impl OffsetCommitter for Commits {
+		fn commit(&self, _topic: &str, partition: i32, offset: i64, sync: bool) -> Result<(), Box<dyn Error + Send + Sync>> {
+			self.0.lock().unwrap().push((partition, offset, sync));
+			Ok(())
+		}
+	}
+
+	fn message(partition: i32, offset: i64) -> KafkaMessage {
+		KafkaMessage { payload: Vec::new(), key: None, topic: "sensors".to_owned(), partition, offset, timestamp: None }
+	}
+
+	#[tokio::test]
+	async fn test_final_commit() {
+		let commits = Arc::new(Commits::default());
+		let offsets = Arc::new(Mutex::new(OffsetTracker::new()));
+		let (sender, receiver) = mpsc::channel(10);
+		let mut source = WorkerSource::new(receiver, commits.clone(), offsets.clone());
+
+		for (partition, offset) in [(0, 7), (1, 3)] {
+			offsets.lock().unwrap().read("sensors", partition, offset);
+			sender.send(message(partition, offset)).await.unwrap();
+		}
+		// Read by another worker, which has not handled it yet
+		offsets.lock().unwrap().read("sensors", 2, 5);
+		drop(sender);
+
+		while source.next().await.unwrap().is_some() {
+			source.commit().await.unwrap();
+		}
+		let mut committed = commits.0.lock().unwrap().clone();
+		committed.sort();
+		assert_eq!(committed, [(0, 8, false), (1, 3, false), (1, 4, false), (2, 5, false)]);
+
+		source.close().await.expect("The final commit should succeed");
+		let mut committed = commits.0.lock().unwrap().split_off(4);
+		committed.sort();
+		assert_eq!(committed, [(0, 8, true), (1, 4, true), (2, 5, true)], "Every partition should be committed synchronously");
+	}
+}
@@ -0,0 +1,62 @@
+use std::time::{Duration, Instant};
+
+// Counts the readings stored since the last summary, to compare batch sizes with the single insert path
+pub struct Throughput {
+	since: Instant,
+	readings: u64,
+	inserts: u64,
+	insert_time: Duration,
+}
+
+impl Throughput {
+	pub fn new(now: Instant) -> Self {
+		Throughput { since: now, readings: 0, inserts: 0, insert_time: Duration::ZERO }
+	}
+
+	pub fn record(&mut self, readings: usize, duration: Duration) {
+		self.readings += readings as u64;
+		self.inserts += 1;
+		self.insert_time += duration;
+	}
+
+	pub fn elapsed(&self, now: Instant) -> Duration {
+		now.saturating_duration_since(self.since)
+	}
+
+	pub fn summary(&mut self, now: Instant) -> String {
+		let elapsed = self.elapsed(now).as_secs_f64();
+		let rate = if elapsed > 0.0 { self.readings as f64 / elapsed } else { 0.0 };
+		let insert_ms = if self.inserts > 0 { self.insert_time.as_secs_f64() * 1000.0 / self.inserts as f64 } else { 0.0 };
+		let summary = format!(
+			"Stored {} sensor readings in {} inserts over {:.1}s: {:.1} readings/s, {:.1}ms per insert",
+			self.readings, self.inserts, elapsed, rate, insert_ms
+		);
+
+		*self = Throughput::new(now);
+		summary
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_summary() {
+		let start = Instant::now();
+		let mut throughput = Throughput::new(start);
+
+		throughput.record(100, Duration::from_millis(20));
+		throughput.record(50, Duration::from_millis(10));
+
+		assert_eq!(
+			throughput.summary(start + Duration::from_secs(2)),
+			"Stored 150 sensor readings in 2 inserts over 2.0s: 75.0 readings/s, 15.0ms per insert"
+		);
+		assert_eq!(throughput.elapsed(start + Duration::from_secs(3)), Duration::from_secs(1));
+		assert_eq!(
+			throughput.summary(start + Duration::from_secs(3)),
+			"Stored 0 sensor readings in 0 inserts over 1.0s: 0.0 readings/s, 0.0ms per insert"
+		);
+	}
+}